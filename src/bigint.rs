@@ -0,0 +1,939 @@
+use crate::integer::Int;
+use cargo_snippet::snippet;
+
+/// 多倍長非負整数
+/// 2^32 進数でリトルエンディアンに保持する
+#[snippet("BIGINT", include = "INT_TRAIT")]
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// 最上位の桁は 0 でない (0 のときは空)
+    digits: Vec<u32>,
+}
+
+/// 多倍長整数 (符号と絶対値で保持する)
+#[snippet("BIGINT")]
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    /// 0 のときは常に `false`
+    negative: bool,
+    magnitude: BigUint,
+}
+
+/// 文字列から多倍長整数への変換に失敗したときのエラー
+#[snippet("BIGINT")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseBigIntError;
+
+/// この桁数未満のかけ算は筆算で行う
+#[snippet("BIGINT")]
+const KARATSUBA_THRESHOLD: usize = 32;
+
+#[snippet("BIGINT")]
+fn trim_digits(v: &mut Vec<u32>) {
+    while v.last() == Some(&0) {
+        v.pop();
+    }
+}
+
+/// 上位の 0 を無視して大小比較する
+#[snippet("BIGINT")]
+fn cmp_digits(a: &[u32], b: &[u32]) -> std::cmp::Ordering {
+    let a = &a[..a.len() - a.iter().rev().take_while(|&&x| x == 0).count()];
+    let b = &b[..b.len() - b.iter().rev().take_while(|&&x| x == 0).count()];
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+#[snippet("BIGINT")]
+fn add_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut ret = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0_u64;
+    for (i, &x) in a.iter().enumerate() {
+        let s = x as u64 + b.get(i).copied().unwrap_or(0) as u64 + carry;
+        ret.push(s as u32);
+        carry = s >> 32;
+    }
+    ret.push(carry as u32);
+    trim_digits(&mut ret);
+    ret
+}
+
+/// a - b (a >= b であること)
+#[snippet("BIGINT")]
+fn sub_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut ret = Vec::with_capacity(a.len());
+    let mut borrow = 0_i64;
+    for (i, &x) in a.iter().enumerate() {
+        let mut t = x as i64 - b.get(i).copied().unwrap_or(0) as i64 - borrow;
+        borrow = 0;
+        if t < 0 {
+            t += 1 << 32;
+            borrow = 1;
+        }
+        ret.push(t as u32);
+    }
+    assert!(
+        borrow == 0 && b.iter().skip(a.len()).all(|&x| x == 0),
+        "attempt to subtract with overflow"
+    );
+    trim_digits(&mut ret);
+    ret
+}
+
+/// `acc[offset..]` に `x` を足し込む
+#[snippet("BIGINT")]
+fn add_digits_at(acc: &mut [u32], x: &[u32], offset: usize) {
+    let mut carry = 0_u64;
+    let mut i = 0;
+    while i < x.len() || carry > 0 {
+        let s = acc[offset + i] as u64 + x.get(i).copied().unwrap_or(0) as u64 + carry;
+        acc[offset + i] = s as u32;
+        carry = s >> 32;
+        i += 1;
+    }
+}
+
+/// 短い方が `KARATSUBA_THRESHOLD` 桁以上なら Karatsuba 法を使う
+/// Time complexity: O(n^1.59)
+#[snippet("BIGINT")]
+fn mul_digits(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let mut ret = vec![0_u32; a.len() + b.len() + 1];
+    if a.len().min(b.len()) < KARATSUBA_THRESHOLD {
+        for (i, &x) in a.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, &y) in b.iter().enumerate() {
+                let t = x as u64 * y as u64 + ret[i + j] as u64 + carry;
+                ret[i + j] = t as u32;
+                carry = t >> 32;
+            }
+            ret[i + b.len()] = carry as u32;
+        }
+    } else {
+        // a = a1 B^m + a0, b = b1 B^m + b0 として
+        // ab = a1 b1 B^2m + ((a0 + a1)(b0 + b1) - a0 b0 - a1 b1) B^m + a0 b0
+        let m = a.len().max(b.len()) / 2;
+        let (a0, a1) = a.split_at(m.min(a.len()));
+        let (b0, b1) = b.split_at(m.min(b.len()));
+        let z0 = mul_digits(a0, b0);
+        let z2 = mul_digits(a1, b1);
+        let z1 = mul_digits(&add_digits(a0, a1), &add_digits(b0, b1));
+        let z1 = sub_digits(&sub_digits(&z1, &z0), &z2);
+        add_digits_at(&mut ret, &z0, 0);
+        add_digits_at(&mut ret, &z1, m);
+        add_digits_at(&mut ret, &z2, 2 * m);
+    }
+    trim_digits(&mut ret);
+    ret
+}
+
+/// 1 桁で割った商と余り
+#[snippet("BIGINT")]
+fn div_rem_digit(a: &[u32], d: u32) -> (Vec<u32>, u32) {
+    assert!(d != 0, "attempt to divide by zero");
+    let mut q = vec![0_u32; a.len()];
+    let mut r = 0_u64;
+    for i in (0..a.len()).rev() {
+        let cur = r << 32 | a[i] as u64;
+        q[i] = (cur / d as u64) as u32;
+        r = cur % d as u64;
+    }
+    trim_digits(&mut q);
+    (q, r as u32)
+}
+
+/// 商と余り (Knuth の Algorithm D)
+/// Time complexity: O(nm)
+#[snippet("BIGINT")]
+fn div_rem_digits(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    assert!(!b.is_empty(), "attempt to divide by zero");
+    if cmp_digits(a, b) == std::cmp::Ordering::Less {
+        let mut r = a.to_vec();
+        trim_digits(&mut r);
+        return (Vec::new(), r);
+    }
+    if b.len() == 1 {
+        let (q, r) = div_rem_digit(a, b[0]);
+        let mut r = vec![r];
+        trim_digits(&mut r);
+        return (q, r);
+    }
+    // 除数の最上位桁の最上位ビットが立つように正規化する
+    let shift = b.last().unwrap().leading_zeros();
+    let shl = |x: &[u32], extra: usize| -> Vec<u32> {
+        let mut ret = vec![0_u32; x.len() + extra];
+        for (i, &d) in x.iter().enumerate() {
+            ret[i] |= d << shift;
+            if shift > 0 && i + 1 < ret.len() {
+                ret[i + 1] = d >> (32 - shift);
+            }
+        }
+        ret
+    };
+    let v = shl(b, 0);
+    let mut u = shl(a, 1);
+    let n = v.len();
+    let m = a.len() - n;
+    let base = 1_u64 << 32;
+    let mut q = vec![0_u32; m + 1];
+    for j in (0..=m).rev() {
+        // 上位 2 桁から商の桁を見積もる (真の値より高々 2 大きい)
+        let num = (u[j + n] as u64) << 32 | u[j + n - 1] as u64;
+        let mut qhat = num / v[n - 1] as u64;
+        let mut rhat = num % v[n - 1] as u64;
+        while qhat >= base || qhat * v[n - 2] as u64 > (rhat << 32 | u[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += v[n - 1] as u64;
+            if rhat >= base {
+                break;
+            }
+        }
+        // u[j..=j + n] -= qhat * v
+        let mut borrow = 0_i64;
+        let mut carry = 0_u64;
+        for i in 0..n {
+            let p = qhat * v[i] as u64 + carry;
+            carry = p >> 32;
+            let t = u[i + j] as i64 - borrow - (p & 0xffff_ffff) as i64;
+            u[i + j] = t as u32;
+            borrow = if t < 0 { 1 } else { 0 };
+        }
+        let t = u[j + n] as i64 - borrow - carry as i64;
+        u[j + n] = t as u32;
+        if t < 0 {
+            // 引きすぎたので 1 回分足し戻す
+            qhat -= 1;
+            let mut c = 0_u64;
+            for i in 0..n {
+                let s = u[i + j] as u64 + v[i] as u64 + c;
+                u[i + j] = s as u32;
+                c = s >> 32;
+            }
+            u[j + n] = u[j + n].wrapping_add(c as u32);
+        }
+        q[j] = qhat as u32;
+    }
+    let mut r: Vec<u32> = (0..n)
+        .map(|i| {
+            let hi = if shift > 0 && i + 1 < n {
+                u[i + 1] << (32 - shift)
+            } else {
+                0
+            };
+            u[i] >> shift | hi
+        })
+        .collect();
+    trim_digits(&mut q);
+    trim_digits(&mut r);
+    (q, r)
+}
+
+#[snippet("BIGINT")]
+impl BigUint {
+    pub fn new() -> Self {
+        Self { digits: Vec::new() }
+    }
+
+    /// 2^32 進表記の各桁 (リトルエンディアン)
+    pub fn digits(&self) -> &[u32] {
+        &self.digits
+    }
+
+    /// 2 進表記での桁数 (0 のときは 0)
+    pub fn bits(&self) -> u64 {
+        match self.digits.last() {
+            Some(&top) => self.digits.len() as u64 * 32 - top.leading_zeros() as u64,
+            None => 0,
+        }
+    }
+
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.digits.len() {
+            0 => Some(0),
+            1 => Some(self.digits[0] as u64),
+            2 => Some((self.digits[1] as u64) << 32 | self.digits[0] as u64),
+            _ => None,
+        }
+    }
+
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = div_rem_digits(&self.digits, &other.digits);
+        (Self { digits: q }, Self { digits: r })
+    }
+
+    /// 結果が負になるときは `None`
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        if self < other {
+            None
+        } else {
+            Some(self - other)
+        }
+    }
+}
+
+#[snippet("BIGINT")]
+impl BigInt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn from_parts(negative: bool, magnitude: BigUint) -> Self {
+        let negative = negative && !magnitude.digits.is_empty();
+        Self {
+            negative,
+            magnitude,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.negative
+    }
+
+    /// 絶対値
+    pub fn magnitude(&self) -> &BigUint {
+        &self.magnitude
+    }
+
+    /// 絶対値の 2^32 進表記の各桁 (リトルエンディアン)
+    pub fn digits(&self) -> &[u32] {
+        &self.magnitude.digits
+    }
+
+    pub fn to_i64(&self) -> Option<i64> {
+        let m = self.magnitude.to_u64()?;
+        if self.negative {
+            if m <= i64::MAX as u64 + 1 {
+                Some((m as i64).wrapping_neg())
+            } else {
+                None
+            }
+        } else if m <= i64::MAX as u64 {
+            Some(m as i64)
+        } else {
+            None
+        }
+    }
+
+    /// 0 方向に丸めた商と、被除数と同じ符号の余り (プリミティブ型の `/` と `%` と同じ)
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let (q, r) = self.magnitude.div_rem(&other.magnitude);
+        (
+            Self::from_parts(self.negative != other.negative, q),
+            Self::from_parts(self.negative, r),
+        )
+    }
+
+    /// オーバーフローしないので常に `Some`
+    pub fn checked_sub(&self, other: &Self) -> Option<Self> {
+        Some(self - other)
+    }
+}
+
+#[snippet("BIGINT")]
+impl From<BigUint> for BigInt {
+    fn from(magnitude: BigUint) -> Self {
+        Self::from_parts(false, magnitude)
+    }
+}
+
+#[snippet("BIGINT")]
+macro_rules! impl_from_unsigned_for_bigint {
+    ( $( $t: ty )* ) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(x: $t) -> Self {
+                    let mut x = x as u128;
+                    let mut digits = Vec::new();
+                    while x > 0 {
+                        digits.push(x as u32);
+                        x >>= 32;
+                    }
+                    Self { digits }
+                }
+            }
+
+            impl From<$t> for BigInt {
+                fn from(x: $t) -> Self {
+                    Self::from_parts(false, BigUint::from(x))
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("BIGINT")]
+impl_from_unsigned_for_bigint!(u8 u16 u32 u64 u128 usize);
+
+#[snippet("BIGINT")]
+macro_rules! impl_from_signed_for_bigint {
+    ( $( $t: ty )* ) => {
+        $(
+            impl From<$t> for BigInt {
+                fn from(x: $t) -> Self {
+                    let x = x as i128;
+                    Self::from_parts(x < 0, BigUint::from(x.unsigned_abs()))
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("BIGINT")]
+impl_from_signed_for_bigint!(i8 i16 i32 i64 i128 isize);
+
+#[snippet("BIGINT")]
+impl std::str::FromStr for BigUint {
+    type Err = ParseBigIntError;
+
+    /// 10 進表記 (先頭の `+` は許す)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.strip_prefix('+').unwrap_or(s);
+        if s.is_empty() || !s.bytes().all(|c| c.is_ascii_digit()) {
+            return Err(ParseBigIntError);
+        }
+        // 上の桁から 9 桁ずつ読む
+        let bytes = s.as_bytes();
+        let head = bytes.len() % 9;
+        let mut digits: Vec<u32> = Vec::new();
+        let chunks = std::iter::once(&bytes[..head]).chain(bytes[head..].chunks(9));
+        for chunk in chunks.filter(|c| !c.is_empty()) {
+            let value = chunk
+                .iter()
+                .fold(0_u64, |acc, &c| acc * 10 + (c - b'0') as u64);
+            let mul = 10_u64.pow(chunk.len() as u32);
+            let mut carry = value;
+            for d in digits.iter_mut() {
+                let t = *d as u64 * mul + carry;
+                *d = t as u32;
+                carry = t >> 32;
+            }
+            while carry > 0 {
+                digits.push(carry as u32);
+                carry >>= 32;
+            }
+        }
+        trim_digits(&mut digits);
+        Ok(Self { digits })
+    }
+}
+
+#[snippet("BIGINT")]
+impl std::str::FromStr for BigInt {
+    type Err = ParseBigIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.strip_prefix('-') {
+            Some(rest) if !rest.starts_with('+') => Ok(Self::from_parts(true, rest.parse()?)),
+            Some(_) => Err(ParseBigIntError),
+            None => Ok(Self::from_parts(false, s.parse()?)),
+        }
+    }
+}
+
+#[snippet("BIGINT")]
+impl std::fmt::Display for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.digits.is_empty() {
+            return write!(f, "0");
+        }
+        // 10^9 で割っていき、下から 9 桁ずつ取り出す
+        let mut chunks = Vec::new();
+        let mut cur = self.digits.clone();
+        while !cur.is_empty() {
+            let (q, r) = div_rem_digit(&cur, 1_000_000_000);
+            chunks.push(r);
+            cur = q;
+        }
+        let mut s = chunks.last().unwrap().to_string();
+        for c in chunks.iter().rev().skip(1) {
+            s.push_str(&format!("{:09}", c));
+        }
+        f.pad_integral(true, "", &s)
+    }
+}
+
+#[snippet("BIGINT")]
+impl std::fmt::Display for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = self.magnitude.to_string();
+        f.pad_integral(!self.negative, "", &s)
+    }
+}
+
+#[snippet("BIGINT")]
+impl std::fmt::Debug for BigUint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[snippet("BIGINT")]
+impl std::fmt::Debug for BigInt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[snippet("BIGINT")]
+impl Ord for BigUint {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        cmp_digits(&self.digits, &other.digits)
+    }
+}
+
+#[snippet("BIGINT")]
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[snippet("BIGINT")]
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        match (self.negative, other.negative) {
+            (false, false) => self.magnitude.cmp(&other.magnitude),
+            (true, true) => other.magnitude.cmp(&self.magnitude),
+            (false, true) => std::cmp::Ordering::Greater,
+            (true, false) => std::cmp::Ordering::Less,
+        }
+    }
+}
+
+#[snippet("BIGINT")]
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[snippet("BIGINT")]
+impl std::ops::Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        let negative = !self.negative;
+        Self::from_parts(negative, self.magnitude)
+    }
+}
+
+#[snippet("BIGINT")]
+impl std::ops::Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Add<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        BigUint {
+            digits: add_digits(&self.digits, &other.digits),
+        }
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Sub<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn sub(self, other: &BigUint) -> BigUint {
+        BigUint {
+            digits: sub_digits(&self.digits, &other.digits),
+        }
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Mul<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        BigUint {
+            digits: mul_digits(&self.digits, &other.digits),
+        }
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Div<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).0
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Rem<&'a BigUint> for &'a BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        self.div_rem(other).1
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Add<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        if self.negative == other.negative {
+            return BigInt::from_parts(self.negative, &self.magnitude + &other.magnitude);
+        }
+        // 符号が異なるときは絶対値の大きい方から小さい方を引く
+        if self.magnitude >= other.magnitude {
+            BigInt::from_parts(self.negative, &self.magnitude - &other.magnitude)
+        } else {
+            BigInt::from_parts(other.negative, &other.magnitude - &self.magnitude)
+        }
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Sub<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        self + &(-other)
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Mul<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_parts(
+            self.negative != other.negative,
+            &self.magnitude * &other.magnitude,
+        )
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Div<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).0
+    }
+}
+
+#[snippet("BIGINT")]
+impl<'a> std::ops::Rem<&'a BigInt> for &'a BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        self.div_rem(other).1
+    }
+}
+
+/// 参照同士の演算から、値を取る演算と代入演算を作る
+#[snippet("BIGINT")]
+macro_rules! forward_bigint_ops {
+    ( $( $t: ty, $op: ident, $method: ident, $op_assign: ident, $method_assign: ident; )* ) => {
+        $(
+            impl std::ops::$op<$t> for $t {
+                type Output = $t;
+
+                fn $method(self, other: $t) -> $t {
+                    std::ops::$op::$method(&self, &other)
+                }
+            }
+
+            impl std::ops::$op<&$t> for $t {
+                type Output = $t;
+
+                fn $method(self, other: &$t) -> $t {
+                    std::ops::$op::$method(&self, other)
+                }
+            }
+
+            impl std::ops::$op<$t> for &$t {
+                type Output = $t;
+
+                fn $method(self, other: $t) -> $t {
+                    std::ops::$op::$method(self, &other)
+                }
+            }
+
+            impl std::ops::$op_assign<$t> for $t {
+                fn $method_assign(&mut self, other: $t) {
+                    *self = std::ops::$op::$method(&*self, &other);
+                }
+            }
+
+            impl std::ops::$op_assign<&$t> for $t {
+                fn $method_assign(&mut self, other: &$t) {
+                    *self = std::ops::$op::$method(&*self, other);
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("BIGINT")]
+forward_bigint_ops! {
+    BigUint, Add, add, AddAssign, add_assign;
+    BigUint, Sub, sub, SubAssign, sub_assign;
+    BigUint, Mul, mul, MulAssign, mul_assign;
+    BigUint, Div, div, DivAssign, div_assign;
+    BigUint, Rem, rem, RemAssign, rem_assign;
+    BigInt, Add, add, AddAssign, add_assign;
+    BigInt, Sub, sub, SubAssign, sub_assign;
+    BigInt, Mul, mul, MulAssign, mul_assign;
+    BigInt, Div, div, DivAssign, div_assign;
+    BigInt, Rem, rem, RemAssign, rem_assign;
+}
+
+#[snippet("BIGINT")]
+macro_rules! impl_int_for_bigint {
+    ( $( $t: ty )* ) => {
+        $(
+            impl Int for $t {
+                fn zero() -> Self {
+                    <$t>::from(0_u32)
+                }
+                fn one() -> Self {
+                    <$t>::from(1_u32)
+                }
+                fn next(self) -> Self {
+                    self + Self::one()
+                }
+                fn prev(self) -> Self {
+                    self - Self::one()
+                }
+                /// オーバーフローしないので常に `Some`
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    Some(self + other)
+                }
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(&self, &other)
+                }
+                /// オーバーフローしないので常に `Some`
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    Some(self * other)
+                }
+                fn saturating_mul(self, other: Self) -> Self {
+                    self * other
+                }
+                /// 絶対値の 2 進表記で立っているビットの数
+                fn count_ones(self) -> u32 {
+                    self.digits().iter().map(|d| d.count_ones()).sum()
+                }
+                /// 絶対値の 2 進表記で末尾に並ぶ 0 の数 (0 のときは 0)
+                fn trailing_zeros(self) -> u32 {
+                    let digits = self.digits();
+                    match digits.iter().position(|&d| d != 0) {
+                        Some(i) => i as u32 * 32 + digits[i].trailing_zeros(),
+                        None => 0,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("BIGINT")]
+impl_int_for_bigint!(BigUint BigInt);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer::{gcd, lcm};
+    use crate::rational::Rational;
+
+    /// xorshift による疑似乱数で、絶対値が 2^(32 len) 未満の整数を作る
+    fn random_digits(seed: &mut u64, len: usize) -> Vec<u32> {
+        (0..len)
+            .map(|_| {
+                *seed ^= *seed << 13;
+                *seed ^= *seed >> 7;
+                *seed ^= *seed << 17;
+                *seed as u32
+            })
+            .collect()
+    }
+
+    fn big(s: &str) -> BigInt {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        for s in [
+            "0",
+            "1",
+            "-1",
+            "4294967295",
+            "4294967296",
+            "-18446744073709551616",
+            "123456789012345678901234567890",
+        ]
+        .iter()
+        {
+            assert_eq!(big(s).to_string(), *s);
+        }
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(big("+00012").to_string(), "12");
+        assert_eq!(format!("{:>5}", big("-12")), "  -12");
+        assert!("".parse::<BigInt>().is_err());
+        assert!("-".parse::<BigInt>().is_err());
+        assert!("-+1".parse::<BigInt>().is_err());
+        assert!("12a".parse::<BigUint>().is_err());
+        assert!("-1".parse::<BigUint>().is_err());
+        assert_eq!(BigInt::from(i128::MIN).to_string(), i128::MIN.to_string());
+        assert_eq!(BigUint::from(u128::MAX).to_string(), u128::MAX.to_string());
+        assert_eq!(BigInt::from(i64::MIN).to_i64(), Some(i64::MIN));
+        assert_eq!(big("9223372036854775808").to_i64(), None);
+    }
+
+    #[test]
+    fn test_arithmetic_small() {
+        let values: Vec<i64> = vec![
+            0,
+            1,
+            -1,
+            7,
+            -7,
+            1 << 32,
+            -(1 << 32),
+            (1 << 32) - 1,
+            i32::MAX as i64 * 3,
+            -123_456_789_012,
+            987_654_321_098_765,
+        ];
+        for &a in values.iter() {
+            for &b in values.iter() {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                let (a, b) = (a as i128, b as i128);
+                assert_eq!((&x + &y).to_string(), (a + b).to_string());
+                assert_eq!((&x - &y).to_string(), (a - b).to_string());
+                assert_eq!((&x * &y).to_string(), (a * b).to_string());
+                assert_eq!(x.cmp(&y), a.cmp(&b));
+                if b != 0 {
+                    assert_eq!((&x / &y).to_string(), (a / b).to_string());
+                    assert_eq!((&x % &y).to_string(), (a % b).to_string());
+                    assert_eq!(
+                        x.div_floor(&y).to_string(),
+                        Int::div_floor(&a, &b).to_string()
+                    );
+                    assert_eq!(
+                        x.mod_floor(&y).to_string(),
+                        Int::mod_floor(&a, &b).to_string()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_div_large() {
+        let mut seed = 88_172_645_463_325_252_u64;
+        for &(la, lb) in [(1, 1), (5, 3), (40, 40), (100, 37), (257, 130), (3, 300)].iter() {
+            let mut a = BigUint {
+                digits: random_digits(&mut seed, la),
+            };
+            let mut b = BigUint {
+                digits: random_digits(&mut seed, lb),
+            };
+            trim_digits(&mut a.digits);
+            trim_digits(&mut b.digits);
+            let p = &a * &b;
+            // Karatsuba の結果を筆算と比べる
+            let mut naive = BigUint::zero();
+            for (i, &d) in b.digits.iter().enumerate() {
+                let mut shifted = vec![0; i];
+                shifted.extend((&a * &BigUint::from(d)).digits);
+                trim_digits(&mut shifted);
+                naive += BigUint { digits: shifted };
+            }
+            assert_eq!(p, naive);
+            assert_eq!(&p / &b, a);
+            assert_eq!(&p / &a, b);
+            let c = &p + &BigUint::from(12345_u32);
+            let (q, r) = c.div_rem(&a);
+            assert!(r < a);
+            assert_eq!(&(&q * &a) + &r, c);
+        }
+    }
+
+    #[test]
+    fn test_div_rem_random() {
+        let mut seed = 2_463_534_242_u64;
+        for _ in 0..2000 {
+            let la = (random_digits(&mut seed, 1)[0] % 8) as usize + 1;
+            let lb = (random_digits(&mut seed, 1)[0] % 5) as usize + 1;
+            // 0xffffffff や 0 の多い桁は見積もりの補正が起きやすい
+            let pick = |seed: &mut u64, len: usize| -> BigUint {
+                let mut digits: Vec<u32> = random_digits(seed, len)
+                    .into_iter()
+                    .map(|d| match d % 4 {
+                        0 => u32::MAX,
+                        1 => 0,
+                        _ => d,
+                    })
+                    .collect();
+                trim_digits(&mut digits);
+                BigUint { digits }
+            };
+            let a = pick(&mut seed, la);
+            let b = pick(&mut seed, lb);
+            if b.is_zero() {
+                continue;
+            }
+            let (q, r) = a.div_rem(&b);
+            assert!(r < b);
+            assert_eq!(&(&q * &b) + &r, a);
+        }
+    }
+
+    #[test]
+    fn test_factorial() {
+        let mut f = BigInt::one();
+        for i in 1..=50_u32 {
+            f *= BigInt::from(i);
+        }
+        assert_eq!(
+            f.to_string(),
+            "30414093201713378043612608166064768844377641568960512000000000000"
+        );
+        assert_eq!(f.clone().trailing_zeros(), 47);
+        assert_eq!(f.clone().pow(2).sqrt_floor(), f);
+        assert_eq!(
+            big("1000000000000000000000000").nth_root_floor(3),
+            big("100000000")
+        );
+    }
+
+    #[test]
+    fn test_int_generic() {
+        let a = big("123456789012345678901234567890");
+        let b = big("987654321098765432109876543210");
+        assert_eq!(gcd(a.clone(), b.clone()), big("9000000000900000000090"));
+        assert_eq!(
+            lcm(a.clone(), b.clone()),
+            big("13548070124980948012498094801236261410")
+        );
+        // H_40 = 1 + 1/2 + ... + 1/40
+        let mut h = Rational::from_integer(BigInt::zero());
+        for i in 1..=40_u32 {
+            h += Rational::new(BigInt::one(), BigInt::from(i));
+        }
+        assert_eq!(format!("{}", h), "2078178381193813/485721041551200");
+        let mut h = Rational::from_integer(0_i128);
+        for i in 1..=40 {
+            h += Rational::new(1, i);
+        }
+        assert_eq!(format!("{}", h), "2078178381193813/485721041551200");
+    }
+}
@@ -80,7 +80,7 @@ mod tests {
 
     #[test]
     fn test_lower_bound() {
-        let v = vec![2, 2, 5, 5, 9];
+        let v = [2, 2, 5, 5, 9];
         assert_eq!(v.lower_bound(&1), 0);
         assert_eq!(v.lower_bound(&2), 0);
         assert_eq!(v.lower_bound(&3), 2);
@@ -97,7 +97,7 @@ mod tests {
 
     #[test]
     fn test_upper_bound() {
-        let v = vec![2, 2, 5, 5, 9];
+        let v = [2, 2, 5, 5, 9];
         assert_eq!(v.upper_bound(&1), 0);
         assert_eq!(v.upper_bound(&2), 2);
         assert_eq!(v.upper_bound(&3), 2);
@@ -114,7 +114,7 @@ mod tests {
 
     #[test]
     fn test_lower_bound_by() {
-        let v = vec![
+        let v = [
             "abc",
             "def",
             "ghi",
@@ -0,0 +1,464 @@
+#![allow(clippy::suspicious_op_assign_impl)]
+use cargo_snippet::snippet;
+
+/// 64 bit ごとのワード列で表した bitset の共通操作
+/// 64 bit 単位でまとめて演算するので、ビットごとのループより 64 倍程度速い
+/// 長さが 64 の倍数でないとき、最後のワードの余った部分は常に 0 に保つ
+#[snippet("BITSET")]
+pub trait BitWords: Clone {
+    /// ビット数
+    fn len(&self) -> usize;
+    fn words(&self) -> &[u64];
+    fn words_mut(&mut self) -> &mut [u64];
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get(&self, i: usize) -> bool {
+        assert!(i < self.len());
+        self.words()[i / 64] >> (i % 64) & 1 == 1
+    }
+
+    fn set(&mut self, i: usize, value: bool) {
+        assert!(i < self.len());
+        if value {
+            self.words_mut()[i / 64] |= 1 << (i % 64);
+        } else {
+            self.words_mut()[i / 64] &= !(1 << (i % 64));
+        }
+    }
+
+    fn flip(&mut self, i: usize) {
+        assert!(i < self.len());
+        self.words_mut()[i / 64] ^= 1 << (i % 64);
+    }
+
+    fn clear(&mut self) {
+        for w in self.words_mut().iter_mut() {
+            *w = 0;
+        }
+    }
+
+    fn count_ones(&self) -> usize {
+        self.words().iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// 立っているビットがひとつもないか
+    fn none(&self) -> bool {
+        self.words().iter().all(|&w| w == 0)
+    }
+
+    /// 最も小さい立っているビットの位置
+    fn first_one(&self) -> Option<usize> {
+        let words = self.words();
+        words
+            .iter()
+            .position(|&w| w != 0)
+            .map(|i| i * 64 + words[i].trailing_zeros() as usize)
+    }
+
+    /// 立っているビットの位置を昇順に列挙する
+    fn ones(&self) -> Vec<usize> {
+        let mut ret = Vec::new();
+        for (i, &w) in self.words().iter().enumerate() {
+            let mut w = w;
+            while w != 0 {
+                ret.push(i * 64 + w.trailing_zeros() as usize);
+                w &= w - 1;
+            }
+        }
+        ret
+    }
+
+    /// 長さを超えた部分のビットを落とす
+    fn mask_last(&mut self) {
+        let rem = self.len() % 64;
+        if rem != 0 {
+            if let Some(last) = self.words_mut().last_mut() {
+                *last &= (1 << rem) - 1;
+            }
+        }
+    }
+
+    /// i 番目のビットを i + shift 番目に移す (長さは変わらない)
+    fn shift_left(&mut self, shift: usize) {
+        let words = self.words_mut();
+        let n = words.len();
+        let word_shift = shift / 64;
+        let bit_shift = shift % 64;
+        for i in (0..n).rev() {
+            let mut w = 0;
+            if i >= word_shift {
+                w = words[i - word_shift] << bit_shift;
+                if bit_shift != 0 && i > word_shift {
+                    w |= words[i - word_shift - 1] >> (64 - bit_shift);
+                }
+            }
+            words[i] = w;
+        }
+        self.mask_last();
+    }
+
+    /// i 番目のビットを i - shift 番目に移す (長さは変わらない)
+    fn shift_right(&mut self, shift: usize) {
+        let words = self.words_mut();
+        let n = words.len();
+        let word_shift = shift / 64;
+        let bit_shift = shift % 64;
+        for i in 0..n {
+            let mut w = 0;
+            if i + word_shift < n {
+                w = words[i + word_shift] >> bit_shift;
+                if bit_shift != 0 && i + word_shift + 1 < n {
+                    w |= words[i + word_shift + 1] << (64 - bit_shift);
+                }
+            }
+            words[i] = w;
+        }
+    }
+
+    /// 全ビットを反転する
+    fn flip_all(&mut self) {
+        for w in self.words_mut().iter_mut() {
+            *w = !*w;
+        }
+        self.mask_last();
+    }
+}
+
+/// 長さを実行時に決める bitset
+#[snippet("BITSET")]
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    len: usize,
+    words: Vec<u64>,
+}
+
+#[snippet("BITSET")]
+impl BitSet {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+}
+
+#[snippet("BITSET")]
+impl BitWords for BitSet {
+    fn len(&self) -> usize {
+        self.len
+    }
+
+    fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    fn words_mut(&mut self) -> &mut [u64] {
+        &mut self.words
+    }
+}
+
+/// 長さ 64 * N で固定の bitset
+/// ヒープ確保をしないので、`BitSet` より小さいサイズで多数持つときに速い
+#[snippet("BITSET")]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedBitSet<const N: usize> {
+    words: [u64; N],
+}
+
+#[snippet("BITSET")]
+impl<const N: usize> FixedBitSet<N> {
+    pub fn new() -> Self {
+        Self { words: [0; N] }
+    }
+}
+
+#[snippet("BITSET")]
+impl<const N: usize> Default for FixedBitSet<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[snippet("BITSET")]
+impl<const N: usize> BitWords for FixedBitSet<N> {
+    fn len(&self) -> usize {
+        64 * N
+    }
+
+    fn words(&self) -> &[u64] {
+        &self.words
+    }
+
+    fn words_mut(&mut self) -> &mut [u64] {
+        &mut self.words
+    }
+}
+
+#[snippet("BITSET")]
+macro_rules! impl_bitset_traits {
+    ( [$( $generics: tt )*] $t: ty ) => {
+        impl<$( $generics )*> std::fmt::Debug for $t {
+            /// 0 番目のビットから順に並べて表示する
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let s: String = (0..self.len())
+                    .map(|i| if self.get(i) { '1' } else { '0' })
+                    .collect();
+                write!(f, "{}", s)
+            }
+        }
+
+        impl_bitset_traits!(@op [$( $generics )*] $t; BitAnd, bitand, BitAndAssign, bitand_assign, &);
+        impl_bitset_traits!(@op [$( $generics )*] $t; BitOr, bitor, BitOrAssign, bitor_assign, |);
+        impl_bitset_traits!(@op [$( $generics )*] $t; BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+        impl<$( $generics )*> std::ops::Not for &$t {
+            type Output = $t;
+
+            fn not(self) -> $t {
+                let mut ret = self.clone();
+                ret.flip_all();
+                ret
+            }
+        }
+
+        impl<$( $generics )*> std::ops::ShlAssign<usize> for $t {
+            fn shl_assign(&mut self, shift: usize) {
+                self.shift_left(shift);
+            }
+        }
+
+        impl<$( $generics )*> std::ops::ShrAssign<usize> for $t {
+            fn shr_assign(&mut self, shift: usize) {
+                self.shift_right(shift);
+            }
+        }
+
+        impl<$( $generics )*> std::ops::Shl<usize> for &$t {
+            type Output = $t;
+
+            fn shl(self, shift: usize) -> $t {
+                let mut ret = self.clone();
+                ret.shift_left(shift);
+                ret
+            }
+        }
+
+        impl<$( $generics )*> std::ops::Shr<usize> for &$t {
+            type Output = $t;
+
+            fn shr(self, shift: usize) -> $t {
+                let mut ret = self.clone();
+                ret.shift_right(shift);
+                ret
+            }
+        }
+    };
+    ( @op [$( $generics: tt )*] $t: ty; $op: ident, $method: ident, $op_assign: ident, $method_assign: ident, $sym: tt ) => {
+        impl<$( $generics )*> std::ops::$op_assign<&$t> for $t {
+            fn $method_assign(&mut self, other: &$t) {
+                assert_eq!(self.len(), other.len());
+                for (a, b) in self.words_mut().iter_mut().zip(other.words().iter()) {
+                    *a = *a $sym *b;
+                }
+            }
+        }
+
+        impl<'a, $( $generics )*> std::ops::$op<&'a $t> for &'a $t {
+            type Output = $t;
+
+            fn $method(self, other: &$t) -> $t {
+                let mut ret = self.clone();
+                std::ops::$op_assign::$method_assign(&mut ret, other);
+                ret
+            }
+        }
+    };
+}
+
+#[snippet("BITSET")]
+impl_bitset_traits!([] BitSet);
+
+#[snippet("BITSET")]
+impl_bitset_traits!([const N: usize] FixedBitSet<N>);
+
+/// GF(2) 上の行列を掃き出し、階数を返す
+/// 左から `cols` 列のみをピボットに使う (拡大係数行列のときは係数部分の列数を渡す)
+/// Time complexity: O(n m^2 / 64)
+#[snippet("BITSET")]
+pub fn gauss_jordan_gf2<B>(rows: &mut [B], cols: usize) -> usize
+where
+    B: BitWords + for<'a> std::ops::BitXorAssign<&'a B>,
+{
+    let mut rank = 0;
+    for col in 0..cols {
+        let pivot = match (rank..rows.len()).find(|&r| rows[r].get(col)) {
+            Some(r) => r,
+            None => continue,
+        };
+        rows.swap(pivot, rank);
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != rank && row.get(col) {
+                *row ^= &pivot_row;
+            }
+        }
+        rank += 1;
+    }
+    rank
+}
+
+/// GF(2) 上の連立一次方程式 `Ax = b` の解をひとつ求める
+/// `a` の各行は長さ m の `BitSet`、`b` は長さ n (= a の行数)
+/// 解がなければ `None`
+#[snippet("BITSET")]
+pub fn solve_linear_system_gf2(a: &[BitSet], b: &BitSet) -> Option<BitSet> {
+    assert_eq!(a.len(), b.len());
+    let m = a.first().map_or(0, |row| row.len());
+    let mut rows: Vec<BitSet> = a
+        .iter()
+        .enumerate()
+        .map(|(i, row)| {
+            assert_eq!(row.len(), m);
+            let mut ext = BitSet::new(m + 1);
+            for j in row.ones() {
+                ext.set(j, true);
+            }
+            ext.set(m, b.get(i));
+            ext
+        })
+        .collect();
+    let rank = gauss_jordan_gf2(&mut rows, m);
+    if rows[rank..].iter().any(|row| row.get(m)) {
+        return None;
+    }
+    let mut x = BitSet::new(m);
+    for row in rows[..rank].iter() {
+        // 掃き出し済みなので、ピボット列以外の自由変数は 0 とすればよい
+        let pivot = row.first_one().unwrap();
+        x.set(pivot, row.get(m));
+    }
+    Some(x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_str(s: &str) -> BitSet {
+        let mut b = BitSet::new(s.len());
+        for (i, c) in s.chars().enumerate() {
+            b.set(i, c == '1');
+        }
+        b
+    }
+
+    #[test]
+    fn test_bitset_basic() {
+        let mut b = BitSet::new(130);
+        b.set(0, true);
+        b.set(64, true);
+        b.set(129, true);
+        assert!(b.get(0) && b.get(64) && b.get(129));
+        assert!(!b.get(1));
+        assert_eq!(b.count_ones(), 3);
+        assert_eq!(b.ones(), vec![0, 64, 129]);
+        b.flip(0);
+        assert_eq!(b.first_one(), Some(64));
+        b.clear();
+        assert!(b.none());
+        assert_eq!(b.first_one(), None);
+        assert_eq!(format!("{:?}", from_str("0110")), "0110");
+    }
+
+    #[test]
+    fn test_bitset_ops() {
+        let a = from_str("1100");
+        let b = from_str("1010");
+        assert_eq!(&a & &b, from_str("1000"));
+        assert_eq!(&a | &b, from_str("1110"));
+        assert_eq!(&a ^ &b, from_str("0110"));
+        assert_eq!(!&a, from_str("0011"));
+        assert_eq!((!&BitSet::new(70)).count_ones(), 70);
+    }
+
+    #[test]
+    fn test_bitset_shift() {
+        let mut b = BitSet::new(200);
+        for &i in [0, 5, 63, 64, 100, 199].iter() {
+            b.set(i, true);
+        }
+        for shift in [0, 1, 5, 63, 64, 65, 128, 150, 199, 200, 300].iter() {
+            let expected_shl: Vec<usize> = b
+                .ones()
+                .iter()
+                .map(|i| i + shift)
+                .filter(|&i| i < 200)
+                .collect();
+            assert_eq!((&b << *shift).ones(), expected_shl);
+            let expected_shr: Vec<usize> = b
+                .ones()
+                .iter()
+                .filter(|&&i| i >= *shift)
+                .map(|i| i - shift)
+                .collect();
+            assert_eq!((&b >> *shift).ones(), expected_shr);
+        }
+    }
+
+    #[test]
+    fn test_fixed_bitset() {
+        let mut a = FixedBitSet::<3>::new();
+        assert_eq!(a.len(), 192);
+        let mut b = BitSet::new(192);
+        for &i in [0, 5, 63, 64, 100, 191].iter() {
+            a.set(i, true);
+            b.set(i, true);
+        }
+        assert_eq!(a.ones(), b.ones());
+        assert_eq!(format!("{:?}", a), format!("{:?}", b));
+        for shift in [0, 1, 63, 64, 65, 150, 192, 300].iter() {
+            assert_eq!((&a << *shift).ones(), (&b << *shift).ones());
+            assert_eq!((&a >> *shift).ones(), (&b >> *shift).ones());
+        }
+        assert_eq!((!&a).count_ones(), 192 - 6);
+        let mut c = FixedBitSet::<3>::default();
+        c.set(5, true);
+        c.set(6, true);
+        assert_eq!((&a & &c).ones(), vec![5]);
+        assert_eq!((&a ^ &c).count_ones(), 6);
+        a.clear();
+        assert!(a.none());
+
+        // 固定長の行でも同じ掃き出しが使える
+        let mut rows = [FixedBitSet::<1>::new(); 3];
+        for (row, bits) in rows.iter_mut().zip([[0, 1], [1, 2], [0, 2]].iter()) {
+            for &i in bits.iter() {
+                row.set(i, true);
+            }
+        }
+        assert_eq!(gauss_jordan_gf2(&mut rows, 3), 2);
+    }
+
+    #[test]
+    fn test_gauss_jordan_gf2() {
+        let mut rows = vec![from_str("110"), from_str("011"), from_str("101")];
+        assert_eq!(gauss_jordan_gf2(&mut rows, 3), 2);
+        let mut rows = vec![from_str("100"), from_str("010"), from_str("001")];
+        assert_eq!(gauss_jordan_gf2(&mut rows, 3), 3);
+    }
+
+    #[test]
+    fn test_solve_linear_system_gf2() {
+        // x0 ^ x1 = 1, x1 ^ x2 = 0, x0 ^ x2 = 1
+        let a = vec![from_str("110"), from_str("011"), from_str("101")];
+        let x = solve_linear_system_gf2(&a, &from_str("101")).unwrap();
+        for (row, bit) in a.iter().zip([true, false, true].iter()) {
+            assert_eq!((row & &x).count_ones() % 2 == 1, *bit);
+        }
+        // x0 ^ x1 = 1, x1 ^ x2 = 1, x0 ^ x2 = 1 has no solution
+        assert!(solve_linear_system_gf2(&a, &from_str("111")).is_none());
+    }
+}
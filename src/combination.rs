@@ -1,39 +1,39 @@
 #![allow(dead_code)]
 
+use crate::integer::{crt, factorize};
+use crate::modulo::{mod_inv, mod_pow, ModInt};
 use cargo_snippet::snippet;
 
 /// 二項係数を mod のもとで求める
+/// 階乗・階乗の逆元・逆元のテーブルを前計算し、順列・重複組合せ・多項係数・カタラン数なども O(1) で求める
+/// mod は max_size より大きい素数であること
 /// cf. [よくやる二項係数 (nCk mod. p)、逆元 (a^-1 mod. p) の求め方 - けんちょんの競プロ精進記録](http://drken1215.hatenablog.com/entry/2018/06/08/210000)
-#[snippet("COMBINATION")]
+#[snippet("COMBINATION", include = "MOD_INT")]
 pub struct Comb {
     max_size: usize,
-    modulo: usize,
-    factorical_table: Vec<usize>,
-    factorical_inverse_table: Vec<usize>,
-    inverse_table: Vec<usize>,
+    modulo: i64,
+    factorical_table: Vec<ModInt>,
+    factorical_inverse_table: Vec<ModInt>,
+    inverse_table: Vec<ModInt>,
 }
 
 #[snippet("COMBINATION")]
 impl Comb {
-    pub fn new(max_size: usize, modulo: usize) -> Self {
+    pub fn new(max_size: usize, modulo: i64) -> Self {
         let max_size = std::cmp::max(10, max_size);
 
         // 10^7 までしか実用的な速度で計算できない
         assert!(max_size <= 10_000_000);
 
-        let mut factorical_table = vec![0; max_size];
-        let mut factorical_inverse_table = vec![0; max_size];
-        let mut inverse_table = vec![0; max_size];
-        factorical_table[0] = 1;
-        factorical_table[1] = 1;
-        factorical_inverse_table[0] = 1;
-        factorical_inverse_table[1] = 1;
-        inverse_table[1] = 1;
+        let one = ModInt::new(1, modulo);
+        let mut factorical_table = vec![one; max_size];
+        let mut factorical_inverse_table = vec![one; max_size];
+        let mut inverse_table = vec![one; max_size];
+        inverse_table[0] = ModInt::new(0, modulo);
         for i in 2..max_size {
-            factorical_table[i] = factorical_table[i - 1] * i % modulo;
-            inverse_table[i] = modulo - inverse_table[modulo % i] * (modulo / i) % modulo;
-            factorical_inverse_table[i] =
-                factorical_inverse_table[i - 1] * inverse_table[i] % modulo;
+            factorical_table[i] = factorical_table[i - 1] * i;
+            inverse_table[i] = -(inverse_table[modulo as usize % i] * (modulo as usize / i));
+            factorical_inverse_table[i] = factorical_inverse_table[i - 1] * inverse_table[i];
         }
         Self {
             max_size,
@@ -44,18 +44,241 @@ impl Comb {
         }
     }
 
-    pub fn calc(&self, n: usize, k: usize) -> usize {
+    fn zero(&self) -> ModInt {
+        ModInt::new(0, self.modulo)
+    }
+
+    /// n!
+    pub fn fact(&self, n: usize) -> ModInt {
+        assert!(n < self.max_size, "n must be less than max_size");
+        self.factorical_table[n]
+    }
+
+    /// 1 / n!
+    pub fn inv_fact(&self, n: usize) -> ModInt {
+        assert!(n < self.max_size, "n must be less than max_size");
+        self.factorical_inverse_table[n]
+    }
+
+    /// 1 / n
+    pub fn inv(&self, n: usize) -> ModInt {
+        assert!(n > 0 && n < self.max_size);
+        self.inverse_table[n]
+    }
+
+    /// nCk
+    pub fn calc(&self, n: usize, k: usize) -> ModInt {
         if n < k {
-            0
+            self.zero()
         } else {
-            self.factorical_table[n]
-                * (self.factorical_inverse_table[k] * self.factorical_inverse_table[n - k]
-                    % self.modulo)
-                % self.modulo
+            self.fact(n) * self.inv_fact(k) * self.inv_fact(n - k)
+        }
+    }
+
+    /// nPk
+    pub fn perm(&self, n: usize, k: usize) -> ModInt {
+        if n < k {
+            self.zero()
+        } else {
+            self.fact(n) * self.inv_fact(n - k)
+        }
+    }
+
+    /// nHk (n 種類から重複を許して k 個選ぶ組合せ) = (n + k - 1)Ck
+    pub fn homo(&self, n: usize, k: usize) -> ModInt {
+        if n == 0 {
+            if k == 0 {
+                ModInt::new(1, self.modulo)
+            } else {
+                self.zero()
+            }
+        } else {
+            self.calc(n + k - 1, k)
+        }
+    }
+
+    /// 多項係数 (k_1 + k_2 + ... + k_m)! / (k_1! k_2! ... k_m!)
+    pub fn multinomial(&self, ks: &[usize]) -> ModInt {
+        let n: usize = ks.iter().sum();
+        ks.iter()
+            .fold(self.fact(n), |acc, &k| acc * self.inv_fact(k))
+    }
+
+    /// n 番目のカタラン数 C(2n, n) / (n + 1)
+    pub fn catalan(&self, n: usize) -> ModInt {
+        self.calc(2 * n, n) * self.inv(n + 1)
+    }
+}
+
+/// Lucas の定理による二項係数 (小さい素数 p を法とする)
+/// n, k は p よりずっと大きくてもよい
+/// 前計算 O(p)、クエリ O(log_p n)
+#[snippet("LUCAS", include = "MOD_INT")]
+pub struct LucasComb {
+    p: usize,
+    factorical_table: Vec<usize>,
+    factorical_inverse_table: Vec<usize>,
+}
+
+#[snippet("LUCAS")]
+impl LucasComb {
+    pub fn new(p: usize) -> Self {
+        let mut factorical_table = vec![1; p];
+        for i in 1..p {
+            factorical_table[i] = factorical_table[i - 1] * i % p;
+        }
+        let mut factorical_inverse_table = vec![1; p];
+        factorical_inverse_table[p - 1] =
+            mod_inv(factorical_table[p - 1] as i64, p as i64) as usize;
+        for i in (1..p).rev() {
+            factorical_inverse_table[i - 1] = factorical_inverse_table[i] * i % p;
+        }
+        Self {
+            p,
+            factorical_table,
+            factorical_inverse_table,
+        }
+    }
+
+    pub fn calc(&self, n: usize, k: usize) -> usize {
+        let p = self.p;
+        let mut n = n;
+        let mut k = k;
+        let mut ret = 1;
+        while k > 0 {
+            let (ni, ki) = (n % p, k % p);
+            if ni < ki {
+                return 0;
+            }
+            ret = ret * self.factorical_table[ni] % p * self.factorical_inverse_table[ki] % p
+                * self.factorical_inverse_table[ni - ki]
+                % p;
+            n /= p;
+            k /= p;
         }
+        ret % p
     }
 }
 
+/// 素数冪 p^e を法とする二項係数
+/// p^e 以下のテーブルを作るので、p^e は 10^7 程度までを想定
+/// 前計算 O(p^e)、クエリ O(log_p n)
+#[snippet("BINOM_MOD", include = "MOD_INT", include = "CRT", include = "INT")]
+pub struct PrimePowerComb {
+    p: usize,
+    e: usize,
+    pe: usize,
+    // table[i] = (1 以上 i 以下の p と互いに素な整数の積) mod p^e
+    table: Vec<usize>,
+}
+
+#[snippet("BINOM_MOD")]
+impl PrimePowerComb {
+    pub fn new(p: usize, e: usize) -> Self {
+        let pe = p.pow(e as u32);
+        let mut table = vec![1; pe + 1];
+        for i in 1..=pe {
+            table[i] = if i % p == 0 {
+                table[i - 1]
+            } else {
+                table[i - 1] * i % pe
+            };
+        }
+        Self { p, e, pe, table }
+    }
+
+    /// 法 p^e
+    pub fn modulo(&self) -> usize {
+        self.pe
+    }
+
+    pub fn calc(&self, n: usize, k: usize) -> usize {
+        if n < k {
+            return 0;
+        }
+        let (p, pe) = (self.p, self.pe);
+        let v = self.count_p(n) - self.count_p(k) - self.count_p(n - k);
+        if v >= self.e {
+            return 0;
+        }
+        let numer = self.factorial_without_p(n);
+        let denom = self.factorial_without_p(k) * self.factorial_without_p(n - k) % pe;
+        let inv = mod_inv(denom as i64, pe as i64) as usize;
+        numer * inv % pe * p.pow(v as u32) % pe
+    }
+
+    /// n! から p の因数をすべて取り除いたものを mod p^e で求める
+    fn factorial_without_p(&self, n: usize) -> usize {
+        let (p, pe) = (self.p, self.pe);
+        let mut n = n;
+        let mut ret = 1;
+        while n > 0 {
+            ret = ret * mod_pow(self.table[pe] as i64, (n / pe) as i64, pe as i64) as usize % pe;
+            ret = ret * self.table[n % pe] % pe;
+            n /= p;
+        }
+        ret
+    }
+
+    /// n! に含まれる p の個数
+    fn count_p(&self, n: usize) -> usize {
+        let mut n = n / self.p;
+        let mut ret = 0;
+        while n > 0 {
+            ret += n;
+            n /= self.p;
+        }
+        ret
+    }
+}
+
+/// 任意の法 m における二項係数
+/// m を素因数分解し、素数冪ごとに求めた値を中国剰余定理でまとめる
+/// 前計算 O(Σ p^e)、クエリ O(Σ log_p n)
+#[snippet("BINOM_MOD")]
+pub struct BinomMod {
+    parts: Vec<PrimePowerComb>,
+}
+
+#[snippet("BINOM_MOD")]
+impl BinomMod {
+    pub fn new(m: usize) -> Self {
+        assert!(m >= 1);
+        let parts = factorize(m)
+            .into_iter()
+            .map(|(p, e)| PrimePowerComb::new(p, e))
+            .collect();
+        Self { parts }
+    }
+
+    pub fn calc(&self, n: usize, k: usize) -> usize {
+        let congruences: Vec<(i64, i64)> = self
+            .parts
+            .iter()
+            .map(|part| (part.calc(n, k) as i64, part.modulo() as i64))
+            .collect();
+        crt(&congruences).unwrap().0 as usize
+    }
+}
+
+/// n が巨大で k が小さいときの二項係数 (素数 modulo)
+/// k < modulo であること
+/// Time complexity: O(k + log modulo)
+#[snippet("BINOM_SMALL_K", include = "MOD_INT")]
+pub fn binom_small_k(n: usize, k: usize, modulo: i64) -> ModInt {
+    if n < k {
+        return ModInt::new(0, modulo);
+    }
+    let mint = |x: usize| ModInt::new((x as u64 % modulo as u64) as i64, modulo);
+    let mut numer = ModInt::new(1, modulo);
+    let mut denom = ModInt::new(1, modulo);
+    for i in 0..k {
+        numer *= mint(n - i);
+        denom *= mint(i + 1);
+    }
+    numer * denom.inv()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -75,7 +298,97 @@ mod tests {
         ];
 
         for test in tests.iter() {
-            assert_eq!(test.2, comb.calc(test.0, test.1));
+            assert_eq!(comb.calc(test.0, test.1), test.2);
+        }
+    }
+
+    #[test]
+    fn test_comb_toolkit() {
+        let modulo = 1_000_000_007;
+        let comb = Comb::new(1_000, modulo);
+        assert_eq!(comb.fact(0), 1);
+        assert_eq!(comb.fact(5), 120);
+        assert_eq!(comb.fact(10) * comb.inv_fact(10), 1);
+        assert_eq!(comb.inv(2) * 2, 1);
+        assert_eq!(comb.inv(999) * 999, 1);
+        assert_eq!(comb.perm(5, 2), 20);
+        assert_eq!(comb.perm(5, 6), 0);
+        assert_eq!(comb.homo(3, 2), 6);
+        assert_eq!(comb.homo(0, 0), 1);
+        assert_eq!(comb.homo(0, 3), 0);
+        assert_eq!(comb.multinomial(&[2, 1, 1]), 12);
+        assert_eq!(comb.multinomial(&[]), 1);
+        let catalans = [1, 1, 2, 5, 14, 42, 132, 429, 1430, 4862];
+        for (n, &c) in catalans.iter().enumerate() {
+            assert_eq!(comb.catalan(n), c);
+        }
+    }
+
+    fn naive_binom_table(n: usize) -> Vec<Vec<u128>> {
+        let mut c = vec![vec![0_u128; n + 1]; n + 1];
+        for i in 0..=n {
+            c[i][0] = 1;
+            for j in 1..=i {
+                c[i][j] = c[i - 1][j - 1] + c[i - 1][j];
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn test_lucas_comb() {
+        let table = naive_binom_table(60);
+        for &p in [2, 3, 5, 7, 13].iter() {
+            let lucas = LucasComb::new(p);
+            for (n, row) in table.iter().enumerate() {
+                for (k, &c) in row.iter().enumerate() {
+                    assert_eq!(lucas.calc(n, k), (c % p as u128) as usize);
+                }
+                assert_eq!(lucas.calc(n, n + 1), 0);
+            }
+        }
+        // C(10^18, 10^9) mod 7
+        let lucas = LucasComb::new(7);
+        assert_eq!(
+            lucas.calc(1_000_000_000_000_000_000, 1_000_000_000),
+            PrimePowerComb::new(7, 1).calc(1_000_000_000_000_000_000, 1_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_binom_mod() {
+        let table = naive_binom_table(60);
+        for &m in [1, 2, 4, 8, 9, 12, 27, 100, 360, 1024, 1001].iter() {
+            let binom = BinomMod::new(m);
+            for (n, row) in table.iter().enumerate() {
+                for (k, &c) in row.iter().enumerate() {
+                    let expected = (c % m as u128) as usize;
+                    assert_eq!(binom.calc(n, k), expected, "C({}, {}) mod {}", n, k, m);
+                }
+                assert_eq!(binom.calc(n, n + 1), 0);
+            }
+        }
+        // 27 = 3^3, C(10^18, 10^9) mod 3^3 と Lucas の結果が mod 3 で一致する
+        let pp = PrimePowerComb::new(3, 3);
+        let c = pp.calc(1_000_000_000_000_000_000, 1_000_000_000);
+        assert_eq!(
+            c % 3,
+            LucasComb::new(3).calc(1_000_000_000_000_000_000, 1_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_binom_small_k() {
+        let modulo = 1_000_000_007;
+        let comb = Comb::new(1_000, modulo);
+        for n in 0..100 {
+            for k in 0..10 {
+                assert_eq!(comb.calc(n, k), binom_small_k(n, k, modulo));
+            }
         }
+        // C(10^18, 2) = 10^18 (10^18 - 1) / 2
+        let n = 1_000_000_000_000_000_000_usize;
+        let expected = (n as u128 * (n as u128 - 1) / 2 % modulo as u128) as i64;
+        assert_eq!(binom_small_k(n, 2, modulo), expected);
     }
 }
@@ -0,0 +1,328 @@
+use crate::integer::Int;
+use crate::rational::Rational;
+use cargo_snippet::snippet;
+
+/// 連分数展開 x = a0 + 1 / (a1 + 1 / (a2 + ...))
+/// a0 = floor(x), a1, a2, ... >= 1 で、長さが 2 以上なら最後の項は 2 以上
+/// Time complexity: O(log(denominator))
+#[snippet("CONTINUED_FRACTION", include = "RATIONAL")]
+pub fn continued_fraction<T>(x: &Rational<T>) -> Vec<T>
+where
+    T: Int,
+{
+    let mut ret = Vec::new();
+    let mut n = x.numer().clone();
+    let mut d = x.denom().clone();
+    while !d.is_zero() {
+        ret.push(n.div_floor(&d));
+        let r = n.mod_floor(&d);
+        n = d;
+        d = r;
+    }
+    ret
+}
+
+/// 連分数 [a0; a1, a2, ...] が表す有理数
+#[snippet("CONTINUED_FRACTION")]
+pub fn from_continued_fraction<T>(a: &[T]) -> Rational<T>
+where
+    T: Int,
+{
+    assert!(!a.is_empty());
+    convergents_of(a).pop().unwrap()
+}
+
+/// 連分数 [a0; a1, ..., ak] の近似分数 [a0], [a0; a1], ..., [a0; a1, ..., ak]
+#[snippet("CONTINUED_FRACTION")]
+fn convergents_of<T>(a: &[T]) -> Vec<Rational<T>>
+where
+    T: Int,
+{
+    // p_i / q_i = [a0; ..., ai] は p_i = a_i p_{i-1} + p_{i-2} を満たす
+    let (mut p0, mut q0) = (T::zero(), T::one());
+    let (mut p1, mut q1) = (T::one(), T::zero());
+    let mut ret = Vec::with_capacity(a.len());
+    for ai in a.iter() {
+        let p2 = ai.clone() * p1.clone() + p0;
+        let q2 = ai.clone() * q1.clone() + q0;
+        ret.push(Rational::new(p2.clone(), q2.clone()));
+        p0 = p1;
+        q0 = q1;
+        p1 = p2;
+        q1 = q2;
+    }
+    ret
+}
+
+/// `x` の連分数展開から得られる近似分数の列 (最後の要素は `x` 自身)
+#[snippet("CONTINUED_FRACTION")]
+pub fn convergents<T>(x: &Rational<T>) -> Vec<Rational<T>>
+where
+    T: Int,
+{
+    convergents_of(&continued_fraction(x))
+}
+
+/// 分母が `max_denominator` 以下の分数のうち `x` に最も近いもの
+/// 等距離のものが 2 つあるときは、連分数展開を打ち切った近似分数の方を返す
+/// (Python の `Fraction.limit_denominator` と同じ)
+/// Time complexity: O(log(denominator))
+#[snippet("CONTINUED_FRACTION")]
+pub fn best_approximation<T>(x: &Rational<T>, max_denominator: T) -> Rational<T>
+where
+    T: Int,
+{
+    assert!(max_denominator >= T::one());
+    if *x.denom() <= max_denominator {
+        return x.clone();
+    }
+    let (mut p0, mut q0) = (T::zero(), T::one());
+    let (mut p1, mut q1) = (T::one(), T::zero());
+    let mut n = x.numer().clone();
+    let mut d = x.denom().clone();
+    loop {
+        let a = n.div_floor(&d);
+        let q2 = q0.clone() + a.clone() * q1.clone();
+        if q2 > max_denominator {
+            break;
+        }
+        let p2 = p0 + a * p1.clone();
+        p0 = p1;
+        q0 = q1;
+        p1 = p2;
+        q1 = q2;
+        let r = n.mod_floor(&d);
+        n = d;
+        d = r;
+    }
+    // 最後の近似分数 p1 / q1 と、分母が上限に収まる最大の中間近似分数を比べる
+    let k = (max_denominator - q0.clone()) / q1.clone();
+    let bound1 = Rational::new(p0 + k.clone() * p1.clone(), q0 + k * q1.clone());
+    let bound2 = Rational::new(p1, q1);
+    // x の分母は大きくなりうるので、差を直接計算せずに 2 つの候補の中点と比べる
+    // (bound1 と bound2 は x を挟んで反対側にある)
+    let mid = (bound1.clone() + bound2.clone()) / T::one().next();
+    let closer_to_bound2 = if bound2 > *x { *x >= mid } else { *x <= mid };
+    if closer_to_bound2 {
+        bound2
+    } else {
+        bound1
+    }
+}
+
+/// Stern–Brocot 木で 1/1 から `x` (> 0) へ至る経路を、('L' または 'R', 回数) の列で返す
+/// Time complexity: O(log(denominator))
+#[snippet("CONTINUED_FRACTION")]
+pub fn stern_brocot_path<T>(x: &Rational<T>) -> Vec<(char, T)>
+where
+    T: Int,
+{
+    assert!(*x > T::zero());
+    // x = [a0; a1, ..., ak] のとき、経路は R^a0 L^a1 R^a2 ... (最後だけ 1 少ない)
+    let mut a = continued_fraction(x);
+    let last = a.pop().unwrap().prev();
+    a.push(last);
+    a.into_iter()
+        .enumerate()
+        .filter(|(_, count)| !count.is_zero())
+        .map(|(i, count)| (if i % 2 == 0 { 'R' } else { 'L' }, count))
+        .collect()
+}
+
+/// `stern_brocot_path` で表される経路の終点
+#[snippet("CONTINUED_FRACTION")]
+pub fn stern_brocot_decode<T>(path: &[(char, T)]) -> Rational<T>
+where
+    T: Int,
+{
+    // 現在の頂点は (a + c) / (b + d) で、部分木は開区間 (a / b, c / d)
+    let (mut a, mut b) = (T::zero(), T::one());
+    let (mut c, mut d) = (T::one(), T::zero());
+    for (dir, count) in path.iter() {
+        match dir {
+            'R' => {
+                a = a + count.clone() * c.clone();
+                b = b + count.clone() * d.clone();
+            }
+            'L' => {
+                c = c + count.clone() * a.clone();
+                d = d + count.clone() * b.clone();
+            }
+            _ => panic!("direction must be 'L' or 'R'"),
+        }
+    }
+    Rational::new(a + c, b + d)
+}
+
+/// 頂点の深さ (1/1 の深さを 0 とする)
+#[snippet("CONTINUED_FRACTION")]
+pub fn stern_brocot_depth<T>(x: &Rational<T>) -> T
+where
+    T: Int,
+{
+    stern_brocot_path(x)
+        .into_iter()
+        .fold(T::zero(), |acc, (_, count)| acc + count)
+}
+
+/// Stern–Brocot 木における `x` と `y` の最小共通祖先
+#[snippet("CONTINUED_FRACTION")]
+pub fn stern_brocot_lca<T>(x: &Rational<T>, y: &Rational<T>) -> Rational<T>
+where
+    T: Int,
+{
+    let px = stern_brocot_path(x);
+    let py = stern_brocot_path(y);
+    let mut common = Vec::new();
+    for ((dx, cx), (dy, cy)) in px.into_iter().zip(py) {
+        if dx != dy {
+            break;
+        }
+        if cx != cy {
+            common.push((dx, cx.min(cy)));
+            break;
+        }
+        common.push((dx, cx));
+    }
+    stern_brocot_decode(&common)
+}
+
+/// `x` の祖先のうち深さが `depth` のもの (`depth` が `x` の深さより大きければ `None`)
+#[snippet("CONTINUED_FRACTION")]
+pub fn stern_brocot_ancestor<T>(x: &Rational<T>, depth: T) -> Option<Rational<T>>
+where
+    T: Int,
+{
+    let mut rest = depth;
+    let mut path = Vec::new();
+    for (dir, count) in stern_brocot_path(x) {
+        if rest.is_zero() {
+            break;
+        }
+        let step = if count < rest { count } else { rest.clone() };
+        rest = rest - step.clone();
+        path.push((dir, step));
+    }
+    if rest.is_zero() {
+        Some(stern_brocot_decode(&path))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_continued_fraction() {
+        let x = Rational::new(415, 93);
+        assert_eq!(continued_fraction(&x), vec![4, 2, 6, 7]);
+        assert_eq!(from_continued_fraction(&[4, 2, 6, 7]), x);
+        assert_eq!(
+            convergents(&x),
+            vec![
+                Rational::new(4, 1),
+                Rational::new(9, 2),
+                Rational::new(58, 13),
+                Rational::new(415, 93)
+            ]
+        );
+        assert_eq!(continued_fraction(&Rational::new(-7, 3)), vec![-3, 1, 2]);
+        assert_eq!(from_continued_fraction(&[-3, 1, 2]), Rational::new(-7, 3));
+        assert_eq!(continued_fraction(&Rational::from_integer(5)), vec![5]);
+        for p in -30..30_i64 {
+            for q in 1..30 {
+                let x = Rational::new(p, q);
+                assert_eq!(from_continued_fraction(&continued_fraction(&x)), x);
+            }
+        }
+    }
+
+    #[test]
+    fn test_best_approximation() {
+        let pi = Rational::new(3_141_592_653_589_793_i64, 1_000_000_000_000_000);
+        assert_eq!(best_approximation(&pi, 1), 3);
+        assert_eq!(best_approximation(&pi, 10), Rational::new(22, 7));
+        assert_eq!(best_approximation(&pi, 100), Rational::new(311, 99));
+        assert_eq!(best_approximation(&pi, 1000), Rational::new(355, 113));
+        assert_eq!(
+            best_approximation(&pi, 100_000),
+            Rational::new(312_689, 99_532)
+        );
+        assert_eq!(
+            best_approximation(&Rational::new(-7, 10), 2),
+            Rational::new(-1, 2)
+        );
+        assert_eq!(best_approximation(&Rational::new(1, 2), 1), 0);
+        assert_eq!(best_approximation(&Rational::new(3, 4), 1), 1);
+        // 等距離の候補が 2 つある場合 (1/4 は 0 と 1/2 の中点)
+        assert_eq!(best_approximation(&Rational::new(1, 4), 2), 0);
+        assert_eq!(best_approximation(&Rational::new(3, 4), 2), 1);
+        assert_eq!(best_approximation(&Rational::new(-3, 4), 2), -1);
+        assert_eq!(best_approximation(&Rational::new(5, 6), 3), 1);
+        assert_eq!(best_approximation(&Rational::new(7, 8), 4), 1);
+        // brute force
+        for p in 0..40_i64 {
+            for q in 1..40 {
+                let x = Rational::new(p, q);
+                for n in 1..10 {
+                    let best = best_approximation(&x, n);
+                    assert!(*best.denom() <= n);
+                    let dist = |r: Rational<i64>| {
+                        let d = r - x;
+                        if d < 0 {
+                            Rational::from_integer(0) - d
+                        } else {
+                            d
+                        }
+                    };
+                    for dq in 1..=n {
+                        let dp = (x * dq).floor().to_integer();
+                        for cand in [dp, dp + 1].iter() {
+                            assert!(dist(best) <= dist(Rational::new(*cand, dq)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_stern_brocot() {
+        let x = Rational::new(5, 3);
+        assert_eq!(stern_brocot_path(&x), vec![('R', 1), ('L', 1), ('R', 1)]);
+        assert_eq!(stern_brocot_path(&Rational::from_integer(1)), vec![]);
+        assert_eq!(stern_brocot_path(&Rational::new(1, 4)), vec![('L', 3)]);
+        assert_eq!(stern_brocot_depth(&x), 3);
+        assert_eq!(
+            stern_brocot_lca(&x, &Rational::new(3, 2)),
+            Rational::new(3, 2)
+        );
+        assert_eq!(
+            stern_brocot_lca(&Rational::new(1, 3), &Rational::new(2, 1)),
+            Rational::from_integer(1)
+        );
+        assert_eq!(
+            stern_brocot_lca(&Rational::new(4, 7), &Rational::new(3, 5)),
+            Rational::new(3, 5)
+        );
+        assert_eq!(
+            stern_brocot_ancestor(&x, 0),
+            Some(Rational::from_integer(1))
+        );
+        assert_eq!(stern_brocot_ancestor(&x, 2), Some(Rational::new(3, 2)));
+        assert_eq!(stern_brocot_ancestor(&x, 3), Some(x));
+        assert_eq!(stern_brocot_ancestor(&x, 4), None);
+        for p in 1..50_i64 {
+            for q in 1..50 {
+                let x = Rational::new(p, q);
+                let path = stern_brocot_path(&x);
+                assert_eq!(stern_brocot_decode(&path), x);
+                // 深さは連分数の項の和 - 1
+                let sum: i64 = continued_fraction(&x).iter().sum();
+                assert_eq!(stern_brocot_depth(&x), sum - 1);
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use crate::modulo::{mod_inv, mod_pow, ModInt};
+use cargo_snippet::snippet;
+
+/// NTT で使う素数とその原始根
+/// 3 つの積は約 5.9 * 10^25 なので、長さ 2^24 程度までの畳み込みなら任意 mod で復元できる
+#[snippet("CONVOLUTION", include = "MOD_INT")]
+const NTT_PRIMES: [(i64, i64); 4] = [
+    (998_244_353, 3),
+    (754_974_721, 11),
+    (167_772_161, 3),
+    (469_762_049, 3),
+];
+
+#[snippet("CONVOLUTION")]
+fn ntt_primitive_root(modulo: i64) -> Option<i64> {
+    NTT_PRIMES
+        .iter()
+        .find(|&&(p, _)| p == modulo)
+        .map(|&(_, g)| g)
+}
+
+/// 数論変換 (in-place)
+/// `a.len()` は 2 冪で、かつ `modulo - 1` を割り切らなければならない
+#[snippet("CONVOLUTION")]
+fn ntt(a: &mut [i64], modulo: i64, root: i64, invert: bool) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let mut w = mod_pow(root, (modulo - 1) / len as i64, modulo);
+        if invert {
+            w = mod_inv(w, modulo);
+        }
+        for chunk in a.chunks_mut(len) {
+            let (lo, hi) = chunk.split_at_mut(len / 2);
+            let mut wn = 1;
+            for (x, y) in lo.iter_mut().zip(hi.iter_mut()) {
+                let u = *x;
+                let v = *y * wn % modulo;
+                *x = if u + v >= modulo {
+                    u + v - modulo
+                } else {
+                    u + v
+                };
+                *y = if u - v < 0 { u - v + modulo } else { u - v };
+                wn = wn * w % modulo;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        let n_inv = mod_inv(n as i64, modulo);
+        for x in a.iter_mut() {
+            *x = *x * n_inv % modulo;
+        }
+    }
+}
+
+/// NTT-friendly な素数 `modulo` (998244353 など) のもとでの畳み込み
+/// 各要素は `0 <= x < modulo` に正規化されている必要はない
+/// Time complexity: O((n + m) log(n + m))
+#[snippet("CONVOLUTION")]
+pub fn convolution_mod(a: &[i64], b: &[i64], modulo: i64) -> Vec<i64> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    if a.len().min(b.len()) <= 32 {
+        let mut ret = vec![0; len];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                ret[i + j] = (ret[i + j] + x.rem_euclid(modulo) * y.rem_euclid(modulo)) % modulo;
+            }
+        }
+        return ret;
+    }
+
+    let root = ntt_primitive_root(modulo).expect("modulo must be an NTT-friendly prime");
+    let size = len.next_power_of_two();
+    assert_eq!((modulo - 1) % size as i64, 0, "convolution is too long");
+    let mut fa = vec![0; size];
+    let mut fb = vec![0; size];
+    for (dst, &x) in fa.iter_mut().zip(a) {
+        *dst = x.rem_euclid(modulo);
+    }
+    for (dst, &x) in fb.iter_mut().zip(b) {
+        *dst = x.rem_euclid(modulo);
+    }
+    ntt(&mut fa, modulo, root, false);
+    ntt(&mut fb, modulo, root, false);
+    for (x, y) in fa.iter_mut().zip(fb.iter()) {
+        *x = *x * *y % modulo;
+    }
+    ntt(&mut fa, modulo, root, true);
+    fa.truncate(len);
+    fa
+}
+
+/// 任意 mod のもとでの畳み込み
+/// 3 つの NTT 素数で畳み込んだ結果を Garner のアルゴリズムで復元する
+/// cf. [任意modでの畳み込み演算をO(n log(n))で - math314のブログ](https://math314.hateblo.jp/entry/2015/05/07/014908)
+#[snippet("CONVOLUTION")]
+pub fn convolution_arbitrary_mod(a: &[ModInt], b: &[ModInt]) -> Vec<ModInt> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let modulo = a[0].modulo();
+    let av: Vec<i64> = a.iter().map(|x| x.value()).collect();
+    let bv: Vec<i64> = b.iter().map(|x| x.value()).collect();
+
+    let (m1, _) = NTT_PRIMES[1];
+    let (m2, _) = NTT_PRIMES[2];
+    let (m3, _) = NTT_PRIMES[3];
+    let c1 = convolution_mod(&av, &bv, m1);
+    let c2 = convolution_mod(&av, &bv, m2);
+    let c3 = convolution_mod(&av, &bv, m3);
+
+    let m1_inv_m2 = mod_inv(m1, m2);
+    let m1m2_inv_m3 = mod_inv(m1 * m2 % m3, m3);
+    let m1m2_mod = (m1 as i128 * m2 as i128 % modulo as i128) as i64;
+
+    c1.iter()
+        .zip(c2.iter())
+        .zip(c3.iter())
+        .map(|((&r1, &r2), &r3)| {
+            let t1 = (r2 - r1).rem_euclid(m2) * m1_inv_m2 % m2;
+            // x = r1 + m1 * t1 (< m1 * m2) satisfies the first two congruences
+            let x = r1 + m1 * t1;
+            let t2 = (r3 - x % m3).rem_euclid(m3) * m1m2_inv_m3 % m3;
+            let res = (x as i128 + m1m2_mod as i128 * t2 as i128) % modulo as i128;
+            ModInt::new(res as i64, modulo)
+        })
+        .collect()
+}
+
+/// `ModInt` 列の畳み込み
+/// mod が NTT-friendly な素数なら NTT 1 回で、そうでなければ任意 mod 畳み込みで計算する
+#[snippet("CONVOLUTION")]
+pub fn convolution(a: &[ModInt], b: &[ModInt]) -> Vec<ModInt> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let modulo = a[0].modulo();
+    let len = a.len() + b.len() - 1;
+    match ntt_primitive_root(modulo) {
+        Some(_) if (modulo - 1) % len.next_power_of_two() as i64 == 0 => {
+            let av: Vec<i64> = a.iter().map(|x| x.value()).collect();
+            let bv: Vec<i64> = b.iter().map(|x| x.value()).collect();
+            convolution_mod(&av, &bv, modulo)
+                .into_iter()
+                .map(|x| ModInt::new(x, modulo))
+                .collect()
+        }
+        _ => convolution_arbitrary_mod(a, b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modulo::IntoModInt;
+
+    fn naive(a: &[i64], b: &[i64], modulo: i64) -> Vec<i64> {
+        let mut ret = vec![0; a.len() + b.len() - 1];
+        for (i, &x) in a.iter().enumerate() {
+            for (j, &y) in b.iter().enumerate() {
+                ret[i + j] = (ret[i + j] + x * y) % modulo;
+            }
+        }
+        ret
+    }
+
+    fn pseudo_random(n: usize, seed: i64, modulo: i64) -> Vec<i64> {
+        let mut x = seed;
+        (0..n)
+            .map(|_| {
+                x = (x * 1_103_515_245 + 12345) % 2_147_483_648;
+                x % modulo
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_convolution_mod() {
+        let modulo = 998_244_353;
+        assert_eq!(
+            convolution_mod(&[1, 2, 3, 4], &[5, 6, 7, 8, 9], modulo),
+            vec![5, 16, 34, 60, 70, 70, 59, 36]
+        );
+        assert!(convolution_mod(&[], &[1, 2], modulo).is_empty());
+
+        let a = pseudo_random(100, 1, modulo);
+        let b = pseudo_random(77, 2, modulo);
+        assert_eq!(convolution_mod(&a, &b, modulo), naive(&a, &b, modulo));
+    }
+
+    #[test]
+    fn test_convolution_arbitrary_mod() {
+        let modulo = 1_000_000_007;
+        let a = pseudo_random(120, 3, modulo);
+        let b = pseudo_random(90, 4, modulo);
+        let am: Vec<ModInt> = a.iter().map(|x| x.mint()).collect();
+        let bm: Vec<ModInt> = b.iter().map(|x| x.mint()).collect();
+        let expected: Vec<ModInt> = naive(&a, &b, modulo).iter().map(|x| x.mint()).collect();
+        assert_eq!(convolution_arbitrary_mod(&am, &bm), expected);
+        assert_eq!(convolution(&am, &bm), expected);
+    }
+
+    #[test]
+    fn test_convolution_ntt_friendly_mod() {
+        let modulo = 998_244_353;
+        let a = pseudo_random(64, 5, modulo);
+        let b = pseudo_random(64, 6, modulo);
+        let am: Vec<ModInt> = a.iter().map(|x| x.mint_with_mod(modulo)).collect();
+        let bm: Vec<ModInt> = b.iter().map(|x| x.mint_with_mod(modulo)).collect();
+        let expected: Vec<ModInt> = naive(&a, &b, modulo)
+            .iter()
+            .map(|x| x.mint_with_mod(modulo))
+            .collect();
+        assert_eq!(convolution(&am, &bm), expected);
+        assert_eq!(convolution_arbitrary_mod(&am, &bm), expected);
+    }
+}
@@ -0,0 +1,202 @@
+use crate::combination::Comb;
+use crate::convolution::convolution;
+use crate::formal_power_series::FormalPowerSeries;
+use crate::modulo::ModInt;
+use cargo_snippet::snippet;
+
+/// 第 1 種スターリング数 (符号なし) の表 c(i, j) (0 <= j <= i <= n)
+/// c(n, k): n 要素の置換のうち、ちょうど k 個の巡回に分解されるものの個数
+/// 符号付きのものは (-1)^(n - k) c(n, k)
+/// Time complexity: O(n^2)
+#[snippet("COUNTING", include = "FPS", include = "COMBINATION")]
+pub fn stirling1_table(n: usize, modulo: i64) -> Vec<Vec<ModInt>> {
+    let mut c = vec![vec![ModInt::new(0, modulo); n + 1]; n + 1];
+    c[0][0] = ModInt::new(1, modulo);
+    for i in 1..=n {
+        for j in 1..=i {
+            c[i][j] = c[i - 1][j - 1] + c[i - 1][j] * (i - 1);
+        }
+    }
+    c
+}
+
+/// 第 2 種スターリング数の表 S(i, j) (0 <= j <= i <= n)
+/// S(n, k): n 個の区別できる要素を k 個の空でないグループに分ける方法の数
+/// Time complexity: O(n^2)
+#[snippet("COUNTING")]
+pub fn stirling2_table(n: usize, modulo: i64) -> Vec<Vec<ModInt>> {
+    let mut s = vec![vec![ModInt::new(0, modulo); n + 1]; n + 1];
+    s[0][0] = ModInt::new(1, modulo);
+    for i in 1..=n {
+        for j in 1..=i {
+            s[i][j] = s[i - 1][j - 1] + s[i - 1][j] * j;
+        }
+    }
+    s
+}
+
+/// 第 1 種スターリング数 (符号なし) c(n, 0), c(n, 1), ..., c(n, n)
+/// x (x + 1) ... (x + n - 1) の係数を分割統治で求める
+/// Time complexity: O(n log^2 n)
+#[snippet("COUNTING")]
+pub fn stirling1_row(n: usize, modulo: i64) -> Vec<ModInt> {
+    fn prod(lo: usize, hi: usize, modulo: i64) -> Vec<ModInt> {
+        // (x + lo) (x + lo + 1) ... (x + hi - 1)
+        if hi - lo == 1 {
+            return vec![ModInt::new(lo as i64, modulo), ModInt::new(1, modulo)];
+        }
+        let mid = (lo + hi) / 2;
+        convolution(&prod(lo, mid, modulo), &prod(mid, hi, modulo))
+    }
+    if n == 0 {
+        return vec![ModInt::new(1, modulo)];
+    }
+    prod(0, n, modulo)
+}
+
+/// 第 2 種スターリング数 S(n, 0), S(n, 1), ..., S(n, n)
+/// S(n, k) = Σ_i (-1)^i / i! * (k - i)^n / (k - i)! を畳み込みで求める
+/// Time complexity: O(n log n)
+#[snippet("COUNTING")]
+pub fn stirling2_row(n: usize, modulo: i64) -> Vec<ModInt> {
+    let comb = Comb::new(n + 1, modulo);
+    let a: Vec<ModInt> = (0..=n)
+        .map(|i| {
+            let x = comb.inv_fact(i);
+            if i % 2 == 0 {
+                x
+            } else {
+                -x
+            }
+        })
+        .collect();
+    let b: Vec<ModInt> = (0..=n)
+        .map(|i| ModInt::new(i as i64, modulo).pow(n as u64) * comb.inv_fact(i))
+        .collect();
+    let mut ret = convolution(&a, &b);
+    ret.truncate(n + 1);
+    ret
+}
+
+/// ベル数 B_0, B_1, ..., B_n
+/// B_n: n 個の区別できる要素をいくつかのグループに分ける方法の数
+/// 指数型母関数 exp(e^x - 1) から求める
+/// Time complexity: O(n log n)
+#[snippet("COUNTING")]
+pub fn bell_numbers(n: usize, modulo: i64) -> Vec<ModInt> {
+    let comb = Comb::new(n + 1, modulo);
+    let mut coef: Vec<ModInt> = (0..=n).map(|i| comb.inv_fact(i)).collect();
+    coef[0] = ModInt::new(0, modulo);
+    let egf = FormalPowerSeries::new(coef, modulo).exp(n + 1);
+    (0..=n).map(|i| egf.get(i) * comb.fact(i)).collect()
+}
+
+/// ベルヌーイ数 B_0, B_1, ..., B_n (B_1 = -1/2 とする流儀)
+/// 指数型母関数 x / (e^x - 1) から求める
+/// Time complexity: O(n log n)
+#[snippet("COUNTING")]
+pub fn bernoulli_numbers(n: usize, modulo: i64) -> Vec<ModInt> {
+    let comb = Comb::new(n + 2, modulo);
+    // (e^x - 1) / x = Σ x^i / (i + 1)!
+    let coef: Vec<ModInt> = (0..=n).map(|i| comb.inv_fact(i + 1)).collect();
+    let egf = FormalPowerSeries::new(coef, modulo).inv(n + 1);
+    (0..=n).map(|i| egf.get(i) * comb.fact(i)).collect()
+}
+
+/// 分割数 p(0), p(1), ..., p(n)
+/// p(n): n を順序を区別せずに正整数の和で表す方法の数
+/// オイラーの五角数定理 Π (1 - x^k) = Σ (-1)^m x^(m (3m - 1) / 2) の逆数として求める
+/// Time complexity: O(n log n)
+#[snippet("COUNTING")]
+pub fn partition_numbers(n: usize, modulo: i64) -> Vec<ModInt> {
+    let mut coef = vec![ModInt::new(0, modulo); n + 1];
+    coef[0] = ModInt::new(1, modulo);
+    for m in 1.. {
+        let sign = if m % 2 == 0 { 1 } else { -1 };
+        let k1 = m * (3 * m - 1) / 2;
+        if k1 > n {
+            break;
+        }
+        coef[k1] += sign;
+        let k2 = m * (3 * m + 1) / 2;
+        if k2 <= n {
+            coef[k2] += sign;
+        }
+    }
+    FormalPowerSeries::new(coef, modulo).inv(n + 1).into_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOD: i64 = 998_244_353;
+
+    fn values(v: &[ModInt]) -> Vec<i64> {
+        v.iter().map(|x| x.value()).collect()
+    }
+
+    #[test]
+    fn test_stirling1() {
+        let table = stirling1_table(10, MOD);
+        assert_eq!(values(&table[4][..5]), vec![0, 6, 11, 6, 1]);
+        for n in 0..=10 {
+            assert_eq!(stirling1_row(n, MOD), table[n][..=n].to_vec());
+        }
+        // arbitrary modulo via three-prime convolution
+        let table = stirling1_table(40, 1_000_000_007);
+        assert_eq!(stirling1_row(40, 1_000_000_007), table[40]);
+    }
+
+    #[test]
+    fn test_stirling2() {
+        let table = stirling2_table(10, MOD);
+        assert_eq!(values(&table[5][..6]), vec![0, 1, 15, 25, 10, 1]);
+        for n in 0..=10 {
+            assert_eq!(stirling2_row(n, MOD), table[n][..=n].to_vec());
+        }
+    }
+
+    #[test]
+    fn test_bell_numbers() {
+        assert_eq!(
+            values(&bell_numbers(10, MOD)),
+            vec![1, 1, 2, 5, 15, 52, 203, 877, 4140, 21147, 115975]
+        );
+        // B_n = Σ_k S(n, k)
+        let table = stirling2_table(30, MOD);
+        let bell = bell_numbers(30, MOD);
+        for n in 0..=30 {
+            let sum = table[n].iter().fold(ModInt::new(0, MOD), |acc, &x| acc + x);
+            assert_eq!(bell[n], sum);
+        }
+    }
+
+    #[test]
+    fn test_bernoulli_numbers() {
+        let b = bernoulli_numbers(8, MOD);
+        let frac = |p: i64, q: i64| ModInt::new(p, MOD) / ModInt::new(q, MOD);
+        let expected = vec![
+            frac(1, 1),
+            frac(-1, 2),
+            frac(1, 6),
+            frac(0, 1),
+            frac(-1, 30),
+            frac(0, 1),
+            frac(1, 42),
+            frac(0, 1),
+            frac(-1, 30),
+        ];
+        assert_eq!(b, expected);
+    }
+
+    #[test]
+    fn test_partition_numbers() {
+        assert_eq!(
+            values(&partition_numbers(12, MOD)),
+            vec![1, 1, 2, 3, 5, 7, 11, 15, 22, 30, 42, 56, 77]
+        );
+        assert_eq!(partition_numbers(100, MOD)[100], 190_569_292);
+        assert_eq!(values(&partition_numbers(0, MOD)), vec![1]);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::integer::Int;
+use crate::modulo::ModInt;
+use cargo_snippet::snippet;
+
+/// Σ_{i = 0}^{n - 1} floor((a * i + b) / m)
+/// a, b は負でもよい (m > 0, n >= 0)
+/// 直線 y = (a x + b) / m の下にある格子点の数え上げに使える
+/// Time complexity: O(log m)
+#[snippet("FLOOR_SUM", include = "INT_TRAIT")]
+pub fn floor_sum<T>(n: T, m: T, a: T, b: T) -> T
+where
+    T: Int,
+{
+    assert!(n >= T::zero());
+    assert!(m > T::zero());
+    let two = T::one().next();
+    let mut ans = T::zero();
+    let (mut n, mut m, mut a, mut b) = (n, m, a, b);
+    // 負の a, b を先に [0, m) に寄せておく
+    if a < T::zero() || a >= m {
+        ans = ans + triangular(n.clone(), two.clone()) * a.div_floor(&m);
+        a = a.mod_floor(&m);
+    }
+    if b < T::zero() || b >= m {
+        ans = ans + n.clone() * b.div_floor(&m);
+        b = b.mod_floor(&m);
+    }
+    loop {
+        if a >= m {
+            ans = ans + triangular(n.clone(), two.clone()) * (a.clone() / m.clone());
+            a = a % m.clone();
+        }
+        if b >= m {
+            ans = ans + n.clone() * (b.clone() / m.clone());
+            b = b % m.clone();
+        }
+        let y_max = a.clone() * n + b;
+        if y_max < m {
+            break;
+        }
+        n = y_max.clone() / m.clone();
+        b = y_max % m.clone();
+        std::mem::swap(&mut m, &mut a);
+    }
+    ans
+}
+
+/// n (n - 1) / 2
+#[snippet("FLOOR_SUM")]
+fn triangular<T>(n: T, two: T) -> T
+where
+    T: Int,
+{
+    if n.clone() % two.clone() == T::zero() {
+        n.clone() / two * (n - T::one())
+    } else {
+        (n.clone() - T::one()) / two * n
+    }
+}
+
+/// `floor_sum` の一般化
+/// q_i = floor((a * i + b) / m) として
+/// (Σ q_i, Σ i * q_i, Σ q_i^2) (i = 0, 1, ..., n - 1) を mod `modulo` で返す
+/// a, b は負でもよい (m > 0, n >= 0)。2 と 6 で割るので `modulo` は 2, 3 と互いに素であること
+/// Time complexity: O(log m)
+#[snippet("FLOOR_SUM", include = "MOD_INT")]
+pub fn floor_sum_generalized(
+    n: i64,
+    m: i64,
+    a: i64,
+    b: i64,
+    modulo: i64,
+) -> (ModInt, ModInt, ModInt) {
+    assert!(n >= 0);
+    assert!(m > 0);
+    // i = 0, 1, ..., n についての和を返す
+    fn rec(n: i128, a: i128, b: i128, c: i128, modulo: i64) -> (ModInt, ModInt, ModInt) {
+        let mint = |x: i128| ModInt::new(x.rem_euclid(modulo as i128) as i64, modulo);
+        let nm = mint(n);
+        let inv2 = mint(2).inv();
+        // Σ i, Σ i^2
+        let s1 = nm * (nm + 1) * inv2;
+        let s2 = nm * (nm + 1) * (nm * 2 + 1) / mint(6);
+        let qa = a.div_euclid(c);
+        let qb = b.div_euclid(c);
+        if qa != 0 || qb != 0 {
+            // q_i = qa * i + qb + floor((ra * i + rb) / c)
+            let (f, g, h) = rec(n, a - qa * c, b - qb * c, c, modulo);
+            let (qa, qb) = (mint(qa), mint(qb));
+            let f2 = qa * s1 + qb * (nm + 1) + f;
+            let g2 = qa * s2 + qb * s1 + g;
+            let h2 =
+                qa * qa * s2 + qb * qb * (nm + 1) + qa * qb * s1 * 2 + qb * f * 2 + qa * g * 2 + h;
+            return (f2, g2, h2);
+        }
+        let m = (a * n + b) / c;
+        if m == 0 {
+            return (mint(0), mint(0), mint(0));
+        }
+        // 縦横を入れ替えて (c, a) の問題に帰着する
+        let (f, g, h) = rec(m - 1, c, c - b - 1, a, modulo);
+        let mm = mint(m);
+        let f2 = nm * mm - f;
+        let g2 = (mm * nm * (nm + 1) - h - f) * inv2;
+        let h2 = nm * mm * (mm + 1) - g * 2 - f * 2 - f2;
+        (f2, g2, h2)
+    }
+
+    if n == 0 {
+        let zero = ModInt::new(0, modulo);
+        return (zero, zero, zero);
+    }
+    rec(n as i128 - 1, a as i128, b as i128, m as i128, modulo)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_floor_sum() {
+        for n in 0..20_i64 {
+            for m in 1..20 {
+                for a in -20..20 {
+                    for b in -20..20 {
+                        let expected: i64 = (0..n).map(|i| (a * i + b).div_euclid(m)).sum();
+                        assert_eq!(floor_sum(n, m, a, b), expected);
+                    }
+                }
+            }
+        }
+        assert_eq!(floor_sum(4_u64, 10, 6, 3), 3);
+        assert_eq!(floor_sum(6_u64, 5, 4, 3), 13);
+        assert_eq!(floor_sum(1_i64, 1, 0, 0), 0);
+        assert_eq!(floor_sum(31415_i64, 92653, 58979, 32384), 314_095_480);
+        assert_eq!(
+            floor_sum(1_000_000_000_i64, 1_000_000_000, 999_999_999, 999_999_999),
+            499_999_999_500_000_000
+        );
+    }
+
+    #[test]
+    fn test_floor_sum_generalized() {
+        let modulo = 998_244_353;
+        for n in 0..15_i64 {
+            for m in 1..12 {
+                for a in -12..12 {
+                    for b in -12..12 {
+                        let q = |i: i64| (a * i + b).div_euclid(m);
+                        let f: i64 = (0..n).map(q).sum();
+                        let g: i64 = (0..n).map(|i| i * q(i)).sum();
+                        let h: i64 = (0..n).map(|i| q(i) * q(i)).sum();
+                        let (f2, g2, h2) = floor_sum_generalized(n, m, a, b, modulo);
+                        assert_eq!(f2, f.rem_euclid(modulo));
+                        assert_eq!(g2, g.rem_euclid(modulo));
+                        assert_eq!(h2, h.rem_euclid(modulo));
+                    }
+                }
+            }
+        }
+        let (f, _, _) = floor_sum_generalized(31415, 92653, 58979, 32384, modulo);
+        assert_eq!(f, 314_095_480);
+    }
+}
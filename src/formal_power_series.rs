@@ -0,0 +1,430 @@
+#![allow(clippy::suspicious_arithmetic_impl)]
+use crate::convolution::convolution;
+use crate::modulo::{mod_sqrt, ModInt};
+use cargo_snippet::snippet;
+
+/// 形式的冪級数
+/// 係数は `ModInt` で持ち、mod は素数であることを仮定する
+/// cf. [Inv of Formal Power Series - Library Checker](https://judge.yosupo.jp/problem/inv_of_formal_power_series)
+#[snippet("FPS", include = "CONVOLUTION", include = "MOD_SQRT")]
+#[derive(Clone, Debug)]
+pub struct FormalPowerSeries {
+    coef: Vec<ModInt>,
+    modulo: i64,
+}
+
+#[snippet("FPS")]
+impl FormalPowerSeries {
+    pub fn new(coef: Vec<ModInt>, modulo: i64) -> Self {
+        Self { coef, modulo }
+    }
+
+    pub fn from_i64(coef: &[i64], modulo: i64) -> Self {
+        Self::new(
+            coef.iter().map(|&x| ModInt::new(x, modulo)).collect(),
+            modulo,
+        )
+    }
+
+    pub fn zero(modulo: i64) -> Self {
+        Self::new(Vec::new(), modulo)
+    }
+
+    pub fn one(modulo: i64) -> Self {
+        Self::new(vec![ModInt::new(1, modulo)], modulo)
+    }
+
+    pub fn len(&self) -> usize {
+        self.coef.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.coef.is_empty()
+    }
+
+    pub fn modulo(&self) -> i64 {
+        self.modulo
+    }
+
+    pub fn coef(&self) -> &[ModInt] {
+        &self.coef
+    }
+
+    pub fn into_vec(self) -> Vec<ModInt> {
+        self.coef
+    }
+
+    /// x^i の係数 (範囲外なら 0)
+    pub fn get(&self, i: usize) -> ModInt {
+        self.coef
+            .get(i)
+            .copied()
+            .unwrap_or_else(|| ModInt::new(0, self.modulo))
+    }
+
+    /// mod x^n で打ち切る (足りなければ 0 で埋める)
+    pub fn prefix(&self, n: usize) -> Self {
+        Self::new((0..n).map(|i| self.get(i)).collect(), self.modulo)
+    }
+
+    /// 末尾の 0 を取り除く
+    pub fn shrink(&mut self) {
+        while self.coef.last().is_some_and(|x| x.value() == 0) {
+            self.coef.pop();
+        }
+    }
+
+    pub fn differential(&self) -> Self {
+        let coef = self
+            .coef
+            .iter()
+            .enumerate()
+            .skip(1)
+            .map(|(i, &x)| x * i as i64)
+            .collect();
+        Self::new(coef, self.modulo)
+    }
+
+    pub fn integral(&self) -> Self {
+        let n = self.coef.len();
+        let inv = inverse_table(n + 1, self.modulo);
+        let mut coef = Vec::with_capacity(n + 1);
+        coef.push(ModInt::new(0, self.modulo));
+        for (i, &x) in self.coef.iter().enumerate() {
+            coef.push(x * inv[i + 1]);
+        }
+        Self::new(coef, self.modulo)
+    }
+
+    /// 1 / f mod x^n
+    /// f の定数項が 0 でないことが必要
+    /// Time complexity: O(n log n)
+    pub fn inv(&self, n: usize) -> Self {
+        assert!(self.get(0).value() != 0, "constant term must be non-zero");
+        let mut g = Self::new(vec![self.get(0).inv()], self.modulo);
+        let mut m = 1;
+        while m < n {
+            m *= 2;
+            // g <- g (2 - f g)
+            let fg = (&self.prefix(m) * &g).prefix(m);
+            let two_minus_fg = &Self::from_i64(&[2], self.modulo) - &fg;
+            g = (&g * &two_minus_fg).prefix(m);
+        }
+        g.prefix(n)
+    }
+
+    /// log f mod x^n
+    /// f の定数項が 1 であることが必要
+    pub fn log(&self, n: usize) -> Self {
+        assert!(self.get(0).value() == 1, "constant term must be 1");
+        let df = self.differential();
+        (&df * &self.inv(n))
+            .prefix(n.saturating_sub(1))
+            .integral()
+            .prefix(n)
+    }
+
+    /// exp f mod x^n
+    /// f の定数項が 0 であることが必要
+    pub fn exp(&self, n: usize) -> Self {
+        assert!(self.get(0).value() == 0, "constant term must be 0");
+        let mut g = Self::one(self.modulo);
+        let mut m = 1;
+        while m < n {
+            m *= 2;
+            // g <- g (1 - log g + f)
+            let t = &(&self.prefix(m) - &g.log(m)) + &Self::one(self.modulo);
+            g = (&g * &t).prefix(m);
+        }
+        g.prefix(n)
+    }
+
+    /// f^k mod x^n
+    pub fn pow(&self, k: u64, n: usize) -> Self {
+        if k == 0 {
+            return Self::one(self.modulo).prefix(n);
+        }
+        let lowest = match self.coef.iter().position(|x| x.value() != 0) {
+            Some(i) => i,
+            None => return Self::zero(self.modulo).prefix(n),
+        };
+        if (lowest as u128) * (k as u128) >= n as u128 {
+            return Self::zero(self.modulo).prefix(n);
+        }
+        let shift = lowest * k as usize;
+        let c = self.coef[lowest];
+        let c_inv = c.inv();
+        let normalized = Self::new(
+            self.coef[lowest..].iter().map(|&x| x * c_inv).collect(),
+            self.modulo,
+        );
+        let k_mod = ModInt::new((k % self.modulo as u64) as i64, self.modulo);
+        let mut g = normalized.log(n - shift) * k_mod;
+        g = g.exp(n - shift) * c.pow(k);
+        let mut coef = vec![ModInt::new(0, self.modulo); shift];
+        coef.extend(g.coef);
+        Self::new(coef, self.modulo)
+    }
+
+    /// sqrt f mod x^n
+    /// 最低次の項の次数が奇数であるなど、平方根が存在しない場合は `None`
+    pub fn sqrt(&self, n: usize) -> Option<Self> {
+        let lowest = match self.coef.iter().position(|x| x.value() != 0) {
+            Some(i) => i,
+            None => return Some(Self::zero(self.modulo).prefix(n)),
+        };
+        if lowest % 2 == 1 {
+            return None;
+        }
+        if lowest / 2 >= n {
+            return Some(Self::zero(self.modulo).prefix(n));
+        }
+        let c = self.coef[lowest];
+        let c_sqrt = ModInt::new(mod_sqrt(c.value(), self.modulo)?, self.modulo);
+        let c_inv = c.inv();
+        let f = Self::new(
+            self.coef[lowest..].iter().map(|&x| x * c_inv).collect(),
+            self.modulo,
+        );
+        let m_total = n - lowest / 2;
+        let inv2 = ModInt::new(2, self.modulo).inv();
+        let mut g = Self::one(self.modulo);
+        let mut m = 1;
+        while m < m_total {
+            m *= 2;
+            // g <- (g + f / g) / 2
+            let t = (&f.prefix(m) * &g.inv(m)).prefix(m);
+            g = (&g + &t) * inv2;
+        }
+        let mut coef = vec![ModInt::new(0, self.modulo); lowest / 2];
+        coef.extend((g.prefix(m_total) * c_sqrt).coef);
+        Some(Self::new(coef, self.modulo))
+    }
+
+    /// 多項式としての除算 (商, 余り)
+    /// Time complexity: O(n log n)
+    pub fn div_rem(&self, other: &Self) -> (Self, Self) {
+        let mut f = self.clone();
+        let mut g = other.clone();
+        f.shrink();
+        g.shrink();
+        assert!(!g.is_empty(), "division by zero polynomial");
+        if f.len() < g.len() {
+            return (Self::zero(self.modulo), f);
+        }
+        let q_len = f.len() - g.len() + 1;
+        let rev_f = Self::new(f.coef.iter().rev().copied().collect(), self.modulo);
+        let rev_g = Self::new(g.coef.iter().rev().copied().collect(), self.modulo);
+        let mut q = (&rev_f.prefix(q_len) * &rev_g.inv(q_len)).prefix(q_len);
+        q.coef.reverse();
+        let mut r = (&f - &(&g * &q)).prefix(g.len() - 1);
+        q.shrink();
+        r.shrink();
+        (q, r)
+    }
+}
+
+/// 1, 2, ..., n - 1 の mod における逆元のテーブル
+#[snippet("FPS")]
+fn inverse_table(n: usize, modulo: i64) -> Vec<ModInt> {
+    let mut inv = vec![ModInt::new(1, modulo); n.max(2)];
+    for i in 2..n {
+        inv[i] = -(inv[modulo as usize % i] * (modulo / i as i64));
+    }
+    inv
+}
+
+#[snippet("FPS")]
+impl<'a> std::ops::Add<&'a FormalPowerSeries> for &'a FormalPowerSeries {
+    type Output = FormalPowerSeries;
+
+    fn add(self, other: &FormalPowerSeries) -> FormalPowerSeries {
+        let n = self.len().max(other.len());
+        let coef = (0..n).map(|i| self.get(i) + other.get(i)).collect();
+        FormalPowerSeries::new(coef, self.modulo)
+    }
+}
+
+#[snippet("FPS")]
+impl<'a> std::ops::Sub<&'a FormalPowerSeries> for &'a FormalPowerSeries {
+    type Output = FormalPowerSeries;
+
+    fn sub(self, other: &FormalPowerSeries) -> FormalPowerSeries {
+        let n = self.len().max(other.len());
+        let coef = (0..n).map(|i| self.get(i) - other.get(i)).collect();
+        FormalPowerSeries::new(coef, self.modulo)
+    }
+}
+
+#[snippet("FPS")]
+impl<'a> std::ops::Mul<&'a FormalPowerSeries> for &'a FormalPowerSeries {
+    type Output = FormalPowerSeries;
+
+    fn mul(self, other: &FormalPowerSeries) -> FormalPowerSeries {
+        FormalPowerSeries::new(convolution(&self.coef, &other.coef), self.modulo)
+    }
+}
+
+#[snippet("FPS")]
+impl std::ops::Add for FormalPowerSeries {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        &self + &other
+    }
+}
+
+#[snippet("FPS")]
+impl std::ops::Sub for FormalPowerSeries {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        &self - &other
+    }
+}
+
+#[snippet("FPS")]
+impl std::ops::Mul for FormalPowerSeries {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        &self * &other
+    }
+}
+
+#[snippet("FPS")]
+impl std::ops::Mul<ModInt> for FormalPowerSeries {
+    type Output = Self;
+
+    fn mul(mut self, other: ModInt) -> Self {
+        for x in self.coef.iter_mut() {
+            *x *= other;
+        }
+        self
+    }
+}
+
+#[snippet("FPS")]
+impl std::ops::Neg for FormalPowerSeries {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        for x in self.coef.iter_mut() {
+            *x = -*x;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOD: i64 = 998_244_353;
+
+    fn fps(coef: &[i64]) -> FormalPowerSeries {
+        FormalPowerSeries::from_i64(coef, MOD)
+    }
+
+    fn values(f: &FormalPowerSeries) -> Vec<i64> {
+        f.coef().iter().map(|x| x.value()).collect()
+    }
+
+    #[test]
+    fn test_ops() {
+        let f = fps(&[1, 2, 3]);
+        let g = fps(&[4, 5]);
+        assert_eq!(values(&(&f + &g)), vec![5, 7, 3]);
+        assert_eq!(values(&(&f - &g)), vec![MOD - 3, MOD - 3, 3]);
+        assert_eq!(values(&(f * g)), vec![4, 13, 22, 15]);
+    }
+
+    #[test]
+    fn test_differential_integral() {
+        let f = fps(&[1, 2, 3, 4]);
+        assert_eq!(values(&f.differential()), vec![2, 6, 12]);
+        assert_eq!(values(&f.differential().integral()), vec![0, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_inv() {
+        // 1 / (1 - x) = 1 + x + x^2 + ...
+        assert_eq!(values(&fps(&[1, MOD - 1]).inv(5)), vec![1, 1, 1, 1, 1]);
+        // Library Checker sample
+        assert_eq!(
+            values(&fps(&[5, 4, 3, 2, 1]).inv(5)),
+            vec![598946612, 718735934, 862483121, 635682004, 163871793]
+        );
+    }
+
+    #[test]
+    fn test_inv_arbitrary_mod() {
+        let modulo = 1_000_000_007;
+        let f = FormalPowerSeries::from_i64(&[3, 1, 4, 1, 5, 9, 2, 6], modulo);
+        let g = f.inv(100);
+        let fg = (&f * &g).prefix(100);
+        assert_eq!(
+            values(&fg),
+            values(&FormalPowerSeries::one(modulo).prefix(100))
+        );
+    }
+
+    #[test]
+    fn test_log() {
+        assert_eq!(
+            values(&fps(&[1, 1, 499122179, 166374064, 291154613]).log(5)),
+            vec![0, 1, 2, 3, 4]
+        );
+    }
+
+    #[test]
+    fn test_exp() {
+        assert_eq!(
+            values(&fps(&[0, 1, 2, 3, 4]).exp(5)),
+            vec![1, 1, 499122179, 166374064, 291154613]
+        );
+        let f = fps(&[0, 3, 1, 4, 1, 5, 9, 2, 6, 5, 3]);
+        assert_eq!(values(&f.exp(40).log(40)), values(&f.prefix(40)));
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(values(&fps(&[0, 0, 9, 12]).pow(3, 4)), vec![0, 0, 0, 0]);
+        assert_eq!(values(&fps(&[1, 1]).pow(4, 6)), vec![1, 4, 6, 4, 1, 0]);
+        assert_eq!(values(&fps(&[0, 2, 1]).pow(2, 6)), vec![0, 0, 4, 4, 1, 0]);
+        assert_eq!(values(&fps(&[0, 0]).pow(0, 3)), vec![1, 0, 0]);
+        assert_eq!(values(&fps(&[0, 0]).pow(2, 3)), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_sqrt() {
+        // (1 + 2x + 3x^2)^2 = 1 + 4x + 10x^2 + 12x^3 + 9x^4
+        let f = fps(&[0, 0, 1, 4, 10, 12, 9]);
+        assert_eq!(values(&f.sqrt(4).unwrap()), vec![0, 1, 2, 3]);
+        assert!(fps(&[0, 1]).sqrt(3).is_none());
+
+        // Library Checker sample
+        assert_eq!(
+            values(&fps(&[0, 0, 9, 12]).sqrt(4).unwrap()),
+            vec![0, 3, 2, 332748117]
+        );
+        // 3 is a quadratic non-residue mod 998244353
+        assert!(fps(&[3, 1]).sqrt(3).is_none());
+        let g = fps(&[5, 1, 4, 1, 5, 9, 2, 6]);
+        let f = (&g * &g).prefix(30);
+        let h = f.sqrt(30).unwrap();
+        assert_eq!(values(&(&h * &h).prefix(30)), values(&f));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        // x^3 + 2x^2 + 3x + 4 = (x + 1)(x^2 + x + 2) + 2
+        let (q, r) = fps(&[4, 3, 2, 1]).div_rem(&fps(&[1, 1]));
+        assert_eq!(values(&q), vec![2, 1, 1]);
+        assert_eq!(values(&r), vec![2]);
+
+        let (q, r) = fps(&[1, 2]).div_rem(&fps(&[1, 1, 1]));
+        assert!(q.is_empty());
+        assert_eq!(values(&r), vec![1, 2]);
+    }
+}
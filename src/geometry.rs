@@ -0,0 +1,1204 @@
+use crate::integer::Int;
+use crate::rational::Rational;
+use cargo_snippet::snippet;
+
+/// 座標に使う数の型 (i64 などの符号付き整数, `Rational`, f64)
+/// 判定は誤差を考慮せずに行うので、f64 のときは丸め誤差に注意
+#[snippet("GEOMETRY", include = "RATIONAL")]
+pub trait Scalar:
+    Clone
+    + PartialOrd
+    + std::ops::Add<Output = Self>
+    + std::ops::Sub<Output = Self>
+    + std::ops::Mul<Output = Self>
+{
+    fn zero() -> Self;
+}
+
+#[snippet("GEOMETRY")]
+macro_rules! impl_scalar_for_numerics {
+    ( $( $t: ty )* ) => {
+        $(
+            impl Scalar for $t {
+                fn zero() -> Self {
+                    0 as $t
+                }
+            }
+        )*
+    };
+}
+
+#[snippet("GEOMETRY")]
+impl_scalar_for_numerics!(i32 i64 i128 isize f64);
+
+#[snippet("GEOMETRY")]
+impl<T: Int> Scalar for Rational<T> {
+    fn zero() -> Self {
+        Rational::from_integer(T::zero())
+    }
+}
+
+#[snippet("GEOMETRY")]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+#[snippet("GEOMETRY")]
+impl<T: Scalar> Point<T> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { x, y }
+    }
+
+    pub fn dot(&self, other: &Self) -> T {
+        self.x.clone() * other.x.clone() + self.y.clone() * other.y.clone()
+    }
+
+    /// 外積 (z 成分)。`other` が反時計回り側にあれば正
+    pub fn cross(&self, other: &Self) -> T {
+        self.x.clone() * other.y.clone() - self.y.clone() * other.x.clone()
+    }
+
+    /// 長さの 2 乗
+    pub fn norm2(&self) -> T {
+        self.dot(self)
+    }
+}
+
+#[snippet("GEOMETRY")]
+impl<T: Scalar> std::ops::Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+#[snippet("GEOMETRY")]
+impl<T: Scalar> std::ops::Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+#[snippet("GEOMETRY")]
+impl<T: Scalar> std::ops::Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Point::new(self.x * rhs.clone(), self.y * rhs)
+    }
+}
+
+/// a -> b -> c が反時計回りなら 1、時計回りなら -1、一直線上なら 0
+#[snippet("GEOMETRY")]
+pub fn orientation<T: Scalar>(a: &Point<T>, b: &Point<T>, c: &Point<T>) -> i32 {
+    let cross = (b.clone() - a.clone()).cross(&(c.clone() - a.clone()));
+    if cross > T::zero() {
+        1
+    } else if cross < T::zero() {
+        -1
+    } else {
+        0
+    }
+}
+
+/// 点 `p` が線分 ab 上 (端点を含む) にあるか
+#[snippet("GEOMETRY")]
+pub fn on_segment<T: Scalar>(p: &Point<T>, a: &Point<T>, b: &Point<T>) -> bool {
+    let pa = a.clone() - p.clone();
+    let pb = b.clone() - p.clone();
+    pa.cross(&pb) == T::zero() && pa.dot(&pb) <= T::zero()
+}
+
+/// 線分 ab と線分 cd が共有点を持つか (端点での接触や重なりを含む)
+#[snippet("GEOMETRY")]
+pub fn segments_intersect<T: Scalar>(
+    a: &Point<T>,
+    b: &Point<T>,
+    c: &Point<T>,
+    d: &Point<T>,
+) -> bool {
+    let (o1, o2) = (orientation(a, b, c), orientation(a, b, d));
+    let (o3, o4) = (orientation(c, d, a), orientation(c, d, b));
+    if o1 * o2 < 0 && o3 * o4 < 0 {
+        return true;
+    }
+    on_segment(c, a, b) || on_segment(d, a, b) || on_segment(a, c, d) || on_segment(b, c, d)
+}
+
+/// 直線 ab と直線 cd の交点 (平行なら `None`)
+/// 途中で座標の 3 乗程度の値が現れるので、`T` はそれが収まる型にすること
+#[snippet("GEOMETRY")]
+pub fn line_intersection<T>(
+    a: &Point<T>,
+    b: &Point<T>,
+    c: &Point<T>,
+    d: &Point<T>,
+) -> Option<Point<Rational<T>>>
+where
+    T: Int + Scalar,
+{
+    let ab = b.clone() - a.clone();
+    let cd = d.clone() - c.clone();
+    let denom = ab.cross(&cd);
+    if denom.is_zero() {
+        return None;
+    }
+    // a + ab * (num / denom)
+    let num = (c.clone() - a.clone()).cross(&cd);
+    let coord =
+        |base: T, dir: T| Rational::new(base * denom.clone() + dir * num.clone(), denom.clone());
+    Some(Point::new(
+        coord(a.x.clone(), ab.x),
+        coord(a.y.clone(), ab.y),
+    ))
+}
+
+/// 線分 ab と線分 cd の交点
+/// 共有点がないとき、または重なっていて共有点が 1 つに定まらないときは `None`
+#[snippet("GEOMETRY")]
+pub fn segment_intersection<T>(
+    a: &Point<T>,
+    b: &Point<T>,
+    c: &Point<T>,
+    d: &Point<T>,
+) -> Option<Point<Rational<T>>>
+where
+    T: Int + Scalar,
+{
+    if !segments_intersect(a, b, c, d) {
+        return None;
+    }
+    if let Some(p) = line_intersection(a, b, c, d) {
+        return Some(p);
+    }
+    // 同一直線上で重なっている: 端点だけで接しているときに限り交点が定まる
+    let mut shared: Vec<&Point<T>> = Vec::new();
+    for (p, s, t) in [(a, c, d), (b, c, d), (c, a, b), (d, a, b)].iter() {
+        if on_segment(p, s, t) && !shared.contains(p) {
+            shared.push(p);
+        }
+    }
+    if shared.len() == 1 {
+        let p = shared[0].clone();
+        Some(Point::new(
+            Rational::from_integer(p.x),
+            Rational::from_integer(p.y),
+        ))
+    } else {
+        None
+    }
+}
+
+/// 多角形の符号付き面積の 2 倍 (頂点が反時計回りなら正)
+/// 整数座標なら結果も整数になる
+/// Time complexity: O(n)
+#[snippet("GEOMETRY")]
+pub fn area2<T: Scalar>(polygon: &[Point<T>]) -> T {
+    let n = polygon.len();
+    (0..n).fold(T::zero(), |acc, i| {
+        acc + polygon[i].cross(&polygon[(i + 1) % n])
+    })
+}
+
+/// 点と多角形の位置関係
+#[snippet("GEOMETRY")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Containment {
+    Outside,
+    OnBoundary,
+    Inside,
+}
+
+/// 点 `p` が多角形 (凸でなくてもよい、向きは問わない) の内部・周上・外部のどれにあるか
+/// `p` から x 軸正方向に伸ばした半直線と辺の交差回数の偶奇で判定する
+/// Time complexity: O(n)
+#[snippet("GEOMETRY")]
+pub fn point_in_polygon<T: Scalar>(polygon: &[Point<T>], p: &Point<T>) -> Containment {
+    let n = polygon.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (s, t) = (&polygon[i], &polygon[(i + 1) % n]);
+        if on_segment(p, s, t) {
+            return Containment::OnBoundary;
+        }
+        let mut a = s.clone() - p.clone();
+        let mut b = t.clone() - p.clone();
+        if a.y > b.y {
+            std::mem::swap(&mut a, &mut b);
+        }
+        // 下端を含み上端を含まない辺が、p より右で半直線と交わるか
+        if a.y <= T::zero() && T::zero() < b.y && a.cross(&b) > T::zero() {
+            inside = !inside;
+        }
+    }
+    if inside {
+        Containment::Inside
+    } else {
+        Containment::Outside
+    }
+}
+
+/// Andrew の monotone chain による凸包
+/// 反時計回りで、(x, y) が辞書順最小の点から始まる。重複する点は 1 つにまとめる
+/// `keep_collinear` が true なら辺上の点も含める (すべて一直線上なら、整列した点をすべて返す)
+/// Time complexity: O(n log n)
+#[snippet("CONVEX_HULL", include = "GEOMETRY")]
+pub fn convex_hull<T: Scalar>(points: &[Point<T>], keep_collinear: bool) -> Vec<Point<T>> {
+    let mut pts = points.to_vec();
+    pts.sort_by(|a, b| {
+        (&a.x, &a.y)
+            .partial_cmp(&(&b.x, &b.y))
+            .expect("coordinates must be comparable")
+    });
+    pts.dedup();
+    let n = pts.len();
+    if n <= 2 {
+        return pts;
+    }
+    if pts
+        .iter()
+        .all(|p| orientation(&pts[0], &pts[n - 1], p) == 0)
+    {
+        return if keep_collinear {
+            pts
+        } else {
+            vec![pts[0].clone(), pts[n - 1].clone()]
+        };
+    }
+    let should_pop = |hull: &[Point<T>], p: &Point<T>| {
+        let o = orientation(&hull[hull.len() - 2], &hull[hull.len() - 1], p);
+        if keep_collinear {
+            o < 0
+        } else {
+            o <= 0
+        }
+    };
+    let mut hull: Vec<Point<T>> = Vec::with_capacity(2 * n);
+    // 下側を左から右へ
+    for p in pts.iter() {
+        while hull.len() >= 2 && should_pop(&hull, p) {
+            hull.pop();
+        }
+        hull.push(p.clone());
+    }
+    // 上側を右から左へ
+    let lower_len = hull.len();
+    for p in pts.iter().rev().skip(1) {
+        while hull.len() > lower_len && should_pop(&hull, p) {
+            hull.pop();
+        }
+        hull.push(p.clone());
+    }
+    // 最後に追加したのは始点
+    hull.pop();
+    hull
+}
+
+/// 凸多角形 (反時計回り) の直径、つまり最も遠い頂点対を rotating calipers で求める
+/// (距離の 2 乗, 点, 点) を返す
+/// Time complexity: O(n)
+#[snippet("CONVEX_HULL")]
+pub fn convex_diameter<T: Scalar>(hull: &[Point<T>]) -> (T, Point<T>, Point<T>) {
+    let n = hull.len();
+    assert!(n > 0);
+    let dist2 = |i: usize, j: usize| (hull[i].clone() - hull[j].clone()).norm2();
+    let mut best = (T::zero(), 0, 0);
+    let mut j = farthest_from_first_edge(hull);
+    for i in 0..n {
+        let ni = (i + 1) % n;
+        let edge = hull[ni].clone() - hull[i].clone();
+        // 辺 i から最も遠い頂点 j まで進める
+        while edge.cross(&(hull[(j + 1) % n].clone() - hull[j].clone())) > T::zero() {
+            j = (j + 1) % n;
+        }
+        for &k in [i, ni].iter() {
+            let d = dist2(k, j);
+            if d > best.0 {
+                best = (d, k, j);
+            }
+        }
+    }
+    (best.0, hull[best.1].clone(), hull[best.2].clone())
+}
+
+/// 辺 0 から最も遠い頂点の番号
+/// 同一直線上の頂点が続くと外積が 0 で止まってしまうので、rotating calipers の初期位置はこれで決める
+#[snippet("CONVEX_HULL")]
+fn farthest_from_first_edge<T: Scalar>(hull: &[Point<T>]) -> usize {
+    let n = hull.len();
+    let edge = hull[1 % n].clone() - hull[0].clone();
+    let height = |k: usize| edge.cross(&(hull[k].clone() - hull[0].clone()));
+    let mut j = 0;
+    for k in 1..n {
+        if height(k) > height(j) {
+            j = k;
+        }
+    }
+    j
+}
+
+/// 点集合の最遠点対 (距離の 2 乗, 点, 点)
+/// Time complexity: O(n log n)
+#[snippet("CONVEX_HULL")]
+pub fn farthest_pair<T: Scalar>(points: &[Point<T>]) -> (T, Point<T>, Point<T>) {
+    convex_diameter(&convex_hull(points, false))
+}
+
+/// 凸多角形 (反時計回り) の幅、つまり多角形を挟む平行な 2 直線の距離の最小値の 2 乗
+/// 最小値は必ずどれかの辺を 1 辺とする向きで達成されるので、辺ごとに最も遠い頂点を rotating calipers で求める
+/// 途中で外積の 2 乗が現れるので、`T` はそれが収まる型にすること (座標が 10^9 以下なら i128)
+/// Time complexity: O(n)
+#[snippet("CONVEX_HULL")]
+pub fn convex_width2<T>(hull: &[Point<T>]) -> Rational<T>
+where
+    T: Int + Scalar,
+{
+    let n = hull.len();
+    if n <= 2 {
+        return Rational::from_integer(<T as Int>::zero());
+    }
+    let mut best: Option<Rational<T>> = None;
+    let mut j = farthest_from_first_edge(hull);
+    for i in 0..n {
+        let edge = hull[(i + 1) % n].clone() - hull[i].clone();
+        let height = |j: usize| edge.cross(&(hull[j].clone() - hull[i].clone()));
+        while height((j + 1) % n) > height(j) {
+            j = (j + 1) % n;
+        }
+        let h = height(j);
+        let w = Rational::new(h.clone() * h, edge.norm2());
+        if best.as_ref().is_none_or(|b| w < *b) {
+            best = Some(w);
+        }
+    }
+    best.unwrap()
+}
+
+/// 凸多角形 (反時計回り) を囲む面積最小の長方形
+/// (面積, 反時計回りの 4 頂点) を返す。頂点数が 2 以下なら面積 0 の退化した長方形を返す
+/// 最小値は必ずどれかの辺を 1 辺とする向きで達成されるので、辺ごとに
+/// 法線方向・辺方向 (正負) の 3 つの極点を rotating calipers で求める
+/// 途中で座標の 3 乗程度の値が現れるので、`T` はそれが収まる型にすること (座標が 10^6 以下なら i64)
+/// Time complexity: O(n)
+#[snippet("CONVEX_HULL")]
+pub fn min_area_rectangle<T>(hull: &[Point<T>]) -> (Rational<T>, Vec<Point<Rational<T>>>)
+where
+    T: Int + Scalar,
+{
+    let n = hull.len();
+    assert!(n > 0);
+    let to_rational = |p: &Point<T>| {
+        Point::new(
+            Rational::from_integer(p.x.clone()),
+            Rational::from_integer(p.y.clone()),
+        )
+    };
+    if n <= 2 {
+        let (a, b) = (to_rational(&hull[0]), to_rational(&hull[n - 1]));
+        return (
+            Rational::from_integer(<T as Int>::zero()),
+            vec![a.clone(), b.clone(), b, a],
+        );
+    }
+    let edge = |i: usize| hull[(i + 1) % n].clone() - hull[i].clone();
+    // 辺 0 について、法線方向に最も遠い頂点 j、辺方向に最大・最小の頂点 k, l
+    let mut j = farthest_from_first_edge(hull);
+    let (mut k, mut l) = (0, 0);
+    for m in 1..n {
+        let d = edge(0).dot(&(hull[m].clone() - hull[0].clone()));
+        if d > edge(0).dot(&(hull[k].clone() - hull[0].clone())) {
+            k = m;
+        }
+        if d < edge(0).dot(&(hull[l].clone() - hull[0].clone())) {
+            l = m;
+        }
+    }
+    let mut best: Option<(Rational<T>, [usize; 4])> = None;
+    for i in 0..n {
+        let e = edge(i);
+        let step = |m: usize| hull[(m + 1) % n].clone() - hull[m].clone();
+        while e.cross(&step(j)) > <T as Int>::zero() {
+            j = (j + 1) % n;
+        }
+        while e.dot(&step(k)) > <T as Int>::zero() {
+            k = (k + 1) % n;
+        }
+        while e.dot(&step(l)) < <T as Int>::zero() {
+            l = (l + 1) % n;
+        }
+        let h = e.cross(&(hull[j].clone() - hull[i].clone()));
+        let w = e.dot(&(hull[k].clone() - hull[l].clone()));
+        let area = Rational::new(h * w, e.norm2());
+        if best.as_ref().is_none_or(|b| area < b.0) {
+            best = Some((area, [i, j, k, l]));
+        }
+    }
+    let (area, [i, j, k, l]) = best.unwrap();
+    // hull[i] を原点、辺 i の向きを x 軸とした座標で長方形を組み立てる
+    let e = edge(i);
+    let n2 = e.norm2();
+    let origin = hull[i].clone();
+    let h = e.cross(&(hull[j].clone() - origin.clone()));
+    let t_max = e.dot(&(hull[k].clone() - origin.clone()));
+    let t_min = e.dot(&(hull[l].clone() - origin.clone()));
+    // origin + e * (t / |e|^2) + (法線) * (s / |e|^2)
+    let corner = |t: T, s: T| {
+        let coord = |base: T, along: T, normal: T| {
+            Rational::new(
+                base * n2.clone() + along * t.clone() + normal * s.clone(),
+                n2.clone(),
+            )
+        };
+        Point::new(
+            coord(
+                origin.x.clone(),
+                e.x.clone(),
+                <T as Int>::zero() - e.y.clone(),
+            ),
+            coord(origin.y.clone(), e.y.clone(), e.x.clone()),
+        )
+    };
+    let zero = <T as Int>::zero();
+    let rect = vec![
+        corner(t_min.clone(), zero.clone()),
+        corner(t_max.clone(), zero),
+        corner(t_max, h.clone()),
+        corner(t_min, h),
+    ];
+    (area, rect)
+}
+
+/// 点 `p` が凸多角形 (反時計回りで、3 頂点が一直線に並ばない) の内部・周上・外部のどれにあるか
+/// 頂点 0 から見た偏角で二分探索する
+/// Time complexity: O(log n)
+#[snippet("CONVEX_HULL")]
+pub fn convex_contains<T: Scalar>(hull: &[Point<T>], p: &Point<T>) -> Containment {
+    let n = hull.len();
+    match n {
+        0 => return Containment::Outside,
+        1 | 2 => {
+            return if on_segment(p, &hull[0], &hull[n - 1]) {
+                Containment::OnBoundary
+            } else {
+                Containment::Outside
+            }
+        }
+        _ => {}
+    }
+    let a = &hull[0];
+    if orientation(a, &hull[1], p) < 0 || orientation(a, &hull[n - 1], p) > 0 {
+        return Containment::Outside;
+    }
+    // p は半直線 a -> hull[lo] と a -> hull[lo + 1] の間にある
+    let (mut lo, mut hi) = (1, n - 1);
+    while hi - lo > 1 {
+        let mid = (lo + hi) / 2;
+        if orientation(a, &hull[mid], p) >= 0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    match orientation(&hull[lo], &hull[lo + 1], p) {
+        o if o < 0 => Containment::Outside,
+        0 => Containment::OnBoundary,
+        _ => {
+            let on_first = lo == 1 && orientation(a, &hull[1], p) == 0;
+            let on_last = lo + 1 == n - 1 && orientation(a, &hull[n - 1], p) == 0;
+            if on_first || on_last {
+                Containment::OnBoundary
+            } else {
+                Containment::Inside
+            }
+        }
+    }
+}
+
+/// 偏角 (x 軸正方向から反時計回りに測った [0, 2π) の角) での比較
+/// 浮動小数点数を使わず、上半平面か下半平面かと外積で比べる。原点は他のどの点よりも小さいとする
+#[snippet("ARGUMENT_SORT", include = "GEOMETRY")]
+pub fn arg_cmp<T: Scalar>(a: &Point<T>, b: &Point<T>) -> std::cmp::Ordering {
+    let half = |p: &Point<T>| {
+        let zero = T::zero();
+        if p.x == zero && p.y == zero {
+            0
+        } else if p.y > zero || (p.y == zero && p.x > zero) {
+            1
+        } else {
+            2
+        }
+    };
+    half(a).cmp(&half(b)).then_with(|| {
+        let cross = a.cross(b);
+        if cross > T::zero() {
+            std::cmp::Ordering::Less
+        } else if cross < T::zero() {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    })
+}
+
+/// 偏角順に安定ソートする (同じ向きの点どうしは元の順序を保つ)
+/// Time complexity: O(n log n)
+#[snippet("ARGUMENT_SORT")]
+pub fn sort_by_argument<T: Scalar>(points: &mut [Point<T>]) {
+    points.sort_by(arg_cmp);
+}
+
+/// 有向直線 p -> q の左側 (境界を含む)
+#[snippet("HALF_PLANE_INTERSECTION", include = "ARGUMENT_SORT")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HalfPlane<T> {
+    pub p: Point<T>,
+    pub q: Point<T>,
+}
+
+#[snippet("HALF_PLANE_INTERSECTION")]
+impl<T> HalfPlane<T>
+where
+    T: Int + Scalar,
+{
+    pub fn new(p: Point<T>, q: Point<T>) -> Self {
+        assert!(p != q);
+        Self { p, q }
+    }
+
+    fn dir(&self) -> Point<T> {
+        self.q.clone() - self.p.clone()
+    }
+
+    /// 点 (x / w, y / w) (w > 0) が境界を除いた外側にあるか
+    fn excludes(&self, (x, y, w): &(T, T, T)) -> bool {
+        let v = Point::new(
+            x.clone() - self.p.x.clone() * w.clone(),
+            y.clone() - self.p.y.clone() * w.clone(),
+        );
+        self.dir().cross(&v) < <T as Int>::zero()
+    }
+
+    /// 境界の直線どうしの交点を (x, y, w) (w > 0) の形で返す。交点は (x / w, y / w)
+    /// 平行なら `None`
+    fn cross_point(&self, other: &Self) -> Option<(T, T, T)> {
+        let (d1, d2) = (self.dir(), other.dir());
+        let w = d1.cross(&d2);
+        if w.is_zero() {
+            return None;
+        }
+        let t = (other.p.clone() - self.p.clone()).cross(&d2);
+        let x = self.p.x.clone() * w.clone() + d1.x * t.clone();
+        let y = self.p.y.clone() * w.clone() + d1.y * t;
+        if w < <T as Int>::zero() {
+            let zero = <T as Int>::zero();
+            Some((zero.clone() - x, zero.clone() - y, zero - w))
+        } else {
+            Some((x, y, w))
+        }
+    }
+}
+
+/// 半平面の共通部分の凸多角形を、(x, y) が辞書順最小の頂点から始まる反時計回りの頂点列 (座標は `Rational`) で返す
+/// 空なら空の `Vec`
+/// 共通部分は有界であること (必要なら十分大きな箱の 4 辺を加える)
+/// 面積が 0 の共通部分 (点や線分) に対しては、重複を除いて 2 点以下の頂点列を返すことがある
+/// 途中で座標の 4 乗程度の値が現れるので、`T` はそれが収まる型にすること (座標が 10^9 以下なら i128)
+/// Time complexity: O(n log n)
+#[snippet("HALF_PLANE_INTERSECTION")]
+pub fn half_plane_intersection<T>(planes: &[HalfPlane<T>]) -> Vec<Point<Rational<T>>>
+where
+    T: Int + Scalar,
+{
+    let mut planes = planes.to_vec();
+    planes.sort_by(|a, b| arg_cmp(&a.dir(), &b.dir()));
+    // 平行な 2 直線の交点は求めないので、それぞれの while で unwrap してよい
+    let mut dq: std::collections::VecDeque<HalfPlane<T>> = std::collections::VecDeque::new();
+    for h in planes {
+        while dq.len() >= 2 {
+            let len = dq.len();
+            if !h.excludes(&dq[len - 2].cross_point(&dq[len - 1]).unwrap()) {
+                break;
+            }
+            dq.pop_back();
+        }
+        while dq.len() >= 2 {
+            if !h.excludes(&dq[0].cross_point(&dq[1]).unwrap()) {
+                break;
+            }
+            dq.pop_front();
+        }
+        if let Some(back) = dq.back() {
+            if h.dir().cross(&back.dir()).is_zero() {
+                // 逆向きで平行なら、共通部分は空か面積 0
+                if h.dir().dot(&back.dir()) < <T as Int>::zero() {
+                    return Vec::new();
+                }
+                // 同じ向きなら内側にある方だけを残す
+                let one = <T as Int>::one();
+                if h.excludes(&(back.p.x.clone(), back.p.y.clone(), one)) {
+                    dq.pop_back();
+                } else {
+                    continue;
+                }
+            }
+        }
+        dq.push_back(h);
+    }
+    while dq.len() >= 3 {
+        let len = dq.len();
+        if !dq[0].excludes(&dq[len - 2].cross_point(&dq[len - 1]).unwrap()) {
+            break;
+        }
+        dq.pop_back();
+    }
+    while dq.len() >= 3 {
+        let len = dq.len();
+        if !dq[len - 1].excludes(&dq[0].cross_point(&dq[1]).unwrap()) {
+            break;
+        }
+        dq.pop_front();
+    }
+    if dq.len() < 3 {
+        return Vec::new();
+    }
+    let mut vertices: Vec<Point<Rational<T>>> = Vec::with_capacity(dq.len());
+    for i in 0..dq.len() {
+        let (x, y, w) = match dq[i].cross_point(&dq[(i + 1) % dq.len()]) {
+            Some(v) => v,
+            // 最後と最初が平行なら共通部分は有界でない
+            None => return Vec::new(),
+        };
+        let v = Point::new(Rational::new(x, w.clone()), Rational::new(y, w));
+        if vertices.last() != Some(&v) {
+            vertices.push(v);
+        }
+    }
+    while vertices.len() >= 2 && vertices.first() == vertices.last() {
+        vertices.pop();
+    }
+    // convex_hull と同じく辞書順最小の頂点から始める
+    if let Some(start) = (0..vertices.len()).min_by(|&i, &j| vertices[i].cmp(&vertices[j])) {
+        vertices.rotate_left(start);
+    }
+    vertices
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i64, y: i64) -> Point<i64> {
+        Point::new(x, y)
+    }
+
+    fn rp(x: (i64, i64), y: (i64, i64)) -> Point<Rational<i64>> {
+        Point::new(Rational::new(x.0, x.1), Rational::new(y.0, y.1))
+    }
+
+    #[test]
+    fn test_point_ops() {
+        let a = p(1, 2);
+        let b = p(3, -1);
+        assert_eq!(a + b, p(4, 1));
+        assert_eq!(a - b, p(-2, 3));
+        assert_eq!(a * 3, p(3, 6));
+        assert_eq!(a.dot(&b), 1);
+        assert_eq!(a.cross(&b), -7);
+        assert_eq!(b.norm2(), 10);
+        assert_eq!(orientation(&p(0, 0), &p(1, 0), &p(0, 1)), 1);
+        assert_eq!(orientation(&p(0, 0), &p(0, 1), &p(1, 0)), -1);
+        assert_eq!(orientation(&p(0, 0), &p(1, 1), &p(3, 3)), 0);
+
+        let r = rp((1, 2), (1, 3));
+        assert_eq!(r.cross(&rp((1, 1), (0, 1))), Rational::new(-1, 3));
+        let f = Point::new(0.5, 1.5);
+        assert_eq!(f.dot(&Point::new(2.0, 2.0)), 4.0);
+    }
+
+    #[test]
+    fn test_on_segment() {
+        assert!(on_segment(&p(1, 1), &p(0, 0), &p(2, 2)));
+        assert!(on_segment(&p(0, 0), &p(0, 0), &p(2, 2)));
+        assert!(on_segment(&p(2, 2), &p(0, 0), &p(2, 2)));
+        assert!(!on_segment(&p(3, 3), &p(0, 0), &p(2, 2)));
+        assert!(!on_segment(&p(1, 0), &p(0, 0), &p(2, 2)));
+        assert!(on_segment(&p(1, 1), &p(1, 1), &p(1, 1)));
+    }
+
+    #[test]
+    fn test_segment_intersection() {
+        // 交差
+        assert!(segments_intersect(&p(0, 0), &p(2, 2), &p(0, 2), &p(2, 0)));
+        assert_eq!(
+            segment_intersection(&p(0, 0), &p(2, 2), &p(0, 2), &p(2, 0)),
+            Some(rp((1, 1), (1, 1)))
+        );
+        assert_eq!(
+            segment_intersection(&p(0, 0), &p(3, 1), &p(0, 1), &p(2, 0)),
+            Some(rp((6, 5), (2, 5)))
+        );
+        // 端点で接する
+        assert_eq!(
+            segment_intersection(&p(0, 0), &p(2, 0), &p(1, 0), &p(1, 5)),
+            Some(rp((1, 1), (0, 1)))
+        );
+        // 交わらない
+        assert!(!segments_intersect(&p(0, 0), &p(1, 1), &p(2, 0), &p(3, -1)));
+        assert_eq!(
+            segment_intersection(&p(0, 0), &p(1, 1), &p(2, 0), &p(1, 2)),
+            None
+        );
+        // 平行
+        assert!(!segments_intersect(&p(0, 0), &p(2, 0), &p(0, 1), &p(2, 1)));
+        // 同一直線上で離れている / 重なる / 端点だけ共有
+        assert!(!segments_intersect(&p(0, 0), &p(1, 0), &p(2, 0), &p(3, 0)));
+        assert!(segments_intersect(&p(0, 0), &p(2, 0), &p(1, 0), &p(3, 0)));
+        assert_eq!(
+            segment_intersection(&p(0, 0), &p(2, 0), &p(1, 0), &p(3, 0)),
+            None
+        );
+        assert_eq!(
+            segment_intersection(&p(0, 0), &p(2, 2), &p(2, 2), &p(3, 3)),
+            Some(rp((2, 1), (2, 1)))
+        );
+        // 直線としては交わる
+        assert_eq!(
+            line_intersection(&p(0, 0), &p(1, 1), &p(2, 0), &p(3, -1)),
+            Some(rp((1, 1), (1, 1)))
+        );
+        assert_eq!(
+            line_intersection(&p(0, 0), &p(1, 1), &p(1, 0), &p(2, 1)),
+            None
+        );
+        // 大きな座標 (途中計算は i128)
+        let big = 1_000_000_000_i128;
+        let q = |x: i128, y: i128| Point::new(x, y);
+        assert_eq!(
+            segment_intersection(
+                &q(-big, -big),
+                &q(big, big - 1),
+                &q(-big, big),
+                &q(big, -big)
+            ),
+            Some(Point::new(
+                Rational::new(big, 4 * big - 1),
+                Rational::new(-big, 4 * big - 1)
+            ))
+        );
+    }
+
+    #[test]
+    fn test_area2() {
+        let square = vec![p(0, 0), p(2, 0), p(2, 2), p(0, 2)];
+        assert_eq!(area2(&square), 8);
+        let rev: Vec<_> = square.iter().rev().cloned().collect();
+        assert_eq!(area2(&rev), -8);
+        assert_eq!(area2(&[p(0, 0), p(1, 0), p(0, 1)]), 1);
+        assert_eq!(area2::<i64>(&[]), 0);
+        let tri = vec![rp((0, 1), (0, 1)), rp((1, 2), (0, 1)), rp((0, 1), (1, 3))];
+        assert_eq!(area2(&tri), Rational::new(1, 6));
+        let tri = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.0, 3.0),
+        ];
+        assert_eq!(area2(&tri), 3.0);
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        // 凹多角形
+        let poly = vec![p(0, 0), p(4, 0), p(4, 4), p(2, 2), p(0, 4)];
+        assert_eq!(point_in_polygon(&poly, &p(1, 1)), Containment::Inside);
+        assert_eq!(point_in_polygon(&poly, &p(3, 1)), Containment::Inside);
+        assert_eq!(point_in_polygon(&poly, &p(2, 3)), Containment::Outside);
+        assert_eq!(point_in_polygon(&poly, &p(2, 2)), Containment::OnBoundary);
+        assert_eq!(point_in_polygon(&poly, &p(1, 3)), Containment::OnBoundary);
+        assert_eq!(point_in_polygon(&poly, &p(4, 2)), Containment::OnBoundary);
+        assert_eq!(point_in_polygon(&poly, &p(5, 0)), Containment::Outside);
+        assert_eq!(point_in_polygon(&poly, &p(-1, 0)), Containment::Outside);
+        // 半直線が頂点を通る場合
+        assert_eq!(point_in_polygon(&poly, &p(1, 2)), Containment::Inside);
+        assert_eq!(point_in_polygon(&poly, &p(-1, 4)), Containment::Outside);
+        let rev: Vec<_> = poly.iter().rev().cloned().collect();
+        // 格子点を数えて Pick の定理 (2S = 2I + B - 2) と比べる
+        let (mut inner, mut boundary) = (0, 0);
+        for x in -1..=5 {
+            for y in -1..=5 {
+                let c = point_in_polygon(&poly, &p(x, y));
+                assert_eq!(c, point_in_polygon(&rev, &p(x, y)));
+                match c {
+                    Containment::Inside => inner += 1,
+                    Containment::OnBoundary => boundary += 1,
+                    Containment::Outside => {}
+                }
+            }
+        }
+        assert_eq!(area2(&poly), 2 * inner + boundary - 2);
+    }
+
+    /// xorshift による疑似乱数で [-range, range] の点を作る
+    fn random_points(seed: &mut u64, n: usize, range: i64) -> Vec<Point<i64>> {
+        let mut next = || {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            (*seed % (2 * range as u64 + 1)) as i64 - range
+        };
+        (0..n).map(|_| Point::new(next(), next())).collect()
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let pts = vec![
+            p(0, 0),
+            p(2, 0),
+            p(4, 0),
+            p(4, 4),
+            p(2, 2),
+            p(0, 4),
+            p(0, 2),
+            p(1, 3),
+            p(4, 0),
+        ];
+        assert_eq!(
+            convex_hull(&pts, false),
+            vec![p(0, 0), p(4, 0), p(4, 4), p(0, 4)]
+        );
+        assert_eq!(
+            convex_hull(&pts, true),
+            vec![p(0, 0), p(2, 0), p(4, 0), p(4, 4), p(0, 4), p(0, 2)]
+        );
+        // 一直線上
+        let line = vec![p(2, 2), p(0, 0), p(1, 1), p(3, 3), p(1, 1)];
+        assert_eq!(convex_hull(&line, false), vec![p(0, 0), p(3, 3)]);
+        assert_eq!(
+            convex_hull(&line, true),
+            vec![p(0, 0), p(1, 1), p(2, 2), p(3, 3)]
+        );
+        assert_eq!(convex_hull(&[p(1, 1), p(1, 1)], true), vec![p(1, 1)]);
+        assert_eq!(convex_hull::<i64>(&[], false), vec![]);
+        let f = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 0.0),
+            Point::new(0.5, 0.2),
+            Point::new(0.0, 1.0),
+        ];
+        assert_eq!(convex_hull(&f, false).len(), 3);
+
+        let mut seed = 88_172_645_463_325_252_u64;
+        for _ in 0..200 {
+            let pts = random_points(&mut seed, 30, 6);
+            let strict = convex_hull(&pts, false);
+            let loose = convex_hull(&pts, true);
+            let n = strict.len();
+            for i in 0..n {
+                assert_eq!(
+                    orientation(&strict[i], &strict[(i + 1) % n], &strict[(i + 2) % n]),
+                    1
+                );
+            }
+            // 周上の点がちょうど loose に含まれる
+            let mut on_boundary: Vec<Point<i64>> = pts
+                .iter()
+                .filter(|q| point_in_polygon(&strict, q) == Containment::OnBoundary)
+                .cloned()
+                .collect();
+            on_boundary.sort();
+            on_boundary.dedup();
+            let mut sorted_loose = loose.clone();
+            sorted_loose.sort();
+            assert_eq!(sorted_loose, on_boundary);
+            assert!(pts
+                .iter()
+                .all(|q| point_in_polygon(&strict, q) != Containment::Outside));
+            assert_eq!(area2(&strict), area2(&loose));
+        }
+    }
+
+    #[test]
+    fn test_diameter_and_width() {
+        let square = vec![p(0, 0), p(3, 0), p(3, 3), p(0, 3)];
+        assert_eq!(convex_diameter(&square).0, 18);
+        assert_eq!(convex_width2(&square), 9);
+        let tri = vec![p(0, 0), p(4, 0), p(0, 3)];
+        assert_eq!(convex_diameter(&tri).0, 25);
+        // 斜辺までの高さ 12 / 5
+        assert_eq!(convex_width2(&tri), Rational::new(144, 25));
+        assert_eq!(farthest_pair(&[p(1, 1)]).0, 0);
+        assert_eq!(farthest_pair(&[p(1, 1), p(4, 5), p(2, 2)]).0, 25);
+        // 同一直線上の頂点を含む凸包
+        let hull = convex_hull(&[p(0, 0), p(1, 0), p(2, 0), p(2, 1), p(0, 1)], true);
+        assert_eq!(hull.len(), 5);
+        assert_eq!(convex_width2(&hull), 1);
+        assert_eq!(convex_diameter(&hull).0, 5);
+        assert_eq!(min_area_rectangle(&hull).0, 2);
+
+        assert_eq!(min_area_rectangle(&square).0, 9);
+        assert_eq!(min_area_rectangle(&tri).0, 12);
+        // 菱形は 45 度傾いた正方形で囲むのが最小
+        let (area, rect) = min_area_rectangle(&[p(2, 0), p(4, 2), p(2, 4), p(0, 2)]);
+        assert_eq!(area, 8);
+        let r = |x: i64, y: i64| Point::new(Rational::from_integer(x), Rational::from_integer(y));
+        assert_eq!(rect, vec![r(2, 0), r(4, 2), r(2, 4), r(0, 2)]);
+        assert_eq!(min_area_rectangle(&[p(1, 1), p(3, 2)]).0, 0);
+
+        let mut seed = 2_463_534_242_u64;
+        for _ in 0..200 {
+            let pts = random_points(&mut seed, 25, 50);
+            let (d, a, b) = farthest_pair(&pts);
+            let mut expected = 0;
+            for a in pts.iter() {
+                for b in pts.iter() {
+                    expected = expected.max((*a - *b).norm2());
+                }
+            }
+            assert_eq!(d, expected);
+            assert_eq!((a - b).norm2(), d);
+
+            // 幅は、各辺について最も遠い頂点までの距離の最小値
+            let hull = convex_hull(&pts, false);
+            let n = hull.len();
+            if n < 3 {
+                continue;
+            }
+            let mut expected: Option<Rational<i64>> = None;
+            for i in 0..n {
+                let e = hull[(i + 1) % n] - hull[i];
+                let h = hull.iter().map(|q| e.cross(&(*q - hull[i]))).max().unwrap();
+                let w = Rational::new(h * h, e.norm2());
+                if expected.is_none_or(|x| w < x) {
+                    expected = Some(w);
+                }
+            }
+            assert_eq!(Some(convex_width2(&hull)), expected);
+
+            // 面積最小の外接長方形も、各辺の向きについての全探索と比べる
+            let mut expected: Option<Rational<i64>> = None;
+            for i in 0..n {
+                let e = hull[(i + 1) % n] - hull[i];
+                let h = hull.iter().map(|q| e.cross(&(*q - hull[i]))).max().unwrap();
+                let t_max = hull.iter().map(|q| e.dot(q)).max().unwrap();
+                let t_min = hull.iter().map(|q| e.dot(q)).min().unwrap();
+                let a = Rational::new(h * (t_max - t_min), e.norm2());
+                if expected.is_none_or(|x| a < x) {
+                    expected = Some(a);
+                }
+            }
+            let (area, rect) = min_area_rectangle(&hull);
+            assert_eq!(Some(area), expected);
+            assert_eq!(area2(&rect), area * 2);
+            for q in hull.iter() {
+                let q = Point::new(Rational::from_integer(q.x), Rational::from_integer(q.y));
+                assert_ne!(point_in_polygon(&rect, &q), Containment::Outside);
+            }
+        }
+
+        // 同一直線上の頂点を残した凸包でも結果は変わらない
+        for _ in 0..300 {
+            let pts = random_points(&mut seed, 20, 4);
+            let strict = convex_hull(&pts, false);
+            let loose = convex_hull(&pts, true);
+            assert_eq!(convex_diameter(&loose).0, convex_diameter(&strict).0);
+            if strict.len() >= 3 {
+                assert_eq!(convex_width2(&loose), convex_width2(&strict));
+                assert_eq!(min_area_rectangle(&loose).0, min_area_rectangle(&strict).0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_convex_contains() {
+        let mut seed = 123_456_789_u64;
+        for _ in 0..100 {
+            let hull = convex_hull(&random_points(&mut seed, 20, 5), false);
+            for x in -6..=6 {
+                for y in -6..=6 {
+                    assert_eq!(
+                        convex_contains(&hull, &p(x, y)),
+                        point_in_polygon(&hull, &p(x, y))
+                    );
+                }
+            }
+        }
+        assert_eq!(
+            convex_contains(&[p(0, 0)], &p(0, 0)),
+            Containment::OnBoundary
+        );
+        assert_eq!(
+            convex_contains(&[p(0, 0), p(2, 2)], &p(1, 1)),
+            Containment::OnBoundary
+        );
+        assert_eq!(
+            convex_contains(&[p(0, 0), p(2, 2)], &p(3, 3)),
+            Containment::Outside
+        );
+    }
+
+    #[test]
+    fn test_sort_by_argument() {
+        let mut pts = vec![
+            p(-1, -1),
+            p(1, 0),
+            p(0, 0),
+            p(0, -2),
+            p(-3, 0),
+            p(1, 1),
+            p(0, 1),
+            p(2, 0),
+            p(1, -1),
+            p(-1, 1),
+            p(2, 2),
+        ];
+        sort_by_argument(&mut pts);
+        assert_eq!(
+            pts,
+            vec![
+                p(0, 0),
+                p(1, 0),
+                p(2, 0),
+                p(1, 1),
+                p(2, 2),
+                p(0, 1),
+                p(-1, 1),
+                p(-3, 0),
+                p(-1, -1),
+                p(0, -2),
+                p(1, -1),
+            ]
+        );
+        // atan2 による偏角と比べる (大きな座標でも正確)
+        let mut seed = 362_436_069_u64;
+        let mut pts = random_points(&mut seed, 300, 1_000_000_000);
+        pts.retain(|q| *q != p(0, 0));
+        sort_by_argument(&mut pts);
+        let angle = |q: &Point<i64>| {
+            let a = (q.y as f64).atan2(q.x as f64);
+            if a < 0.0 {
+                a + 2.0 * std::f64::consts::PI
+            } else {
+                a
+            }
+        };
+        for w in pts.windows(2) {
+            assert!(arg_cmp(&w[0], &w[1]) != std::cmp::Ordering::Greater);
+            assert!(angle(&w[0]) <= angle(&w[1]) + 1e-9);
+        }
+    }
+
+    /// 多角形を半平面で切り取る (Sutherland–Hodgman)
+    fn clip(polygon: &[Point<Rational<i128>>], h: &HalfPlane<i128>) -> Vec<Point<Rational<i128>>> {
+        let r =
+            |q: &Point<i128>| Point::new(Rational::from_integer(q.x), Rational::from_integer(q.y));
+        let (hp, d) = (r(&h.p), r(&h.q) - r(&h.p));
+        let side = |q: &Point<Rational<i128>>| d.cross(&(*q - hp));
+        let zero = Rational::from_integer(0);
+        let mut ret = Vec::new();
+        for i in 0..polygon.len() {
+            let (s, e) = (polygon[i], polygon[(i + 1) % polygon.len()]);
+            let (s_in, e_in) = (side(&s) >= zero, side(&e) >= zero);
+            if s_in != e_in {
+                let t = side(&s) / (side(&s) - side(&e));
+                ret.push(s + (e - s) * t);
+            }
+            if e_in {
+                ret.push(e);
+            }
+        }
+        ret
+    }
+
+    #[test]
+    fn test_half_plane_intersection() {
+        let q = |x: i128, y: i128| Point::new(x, y);
+        let r = |x: i128, y: i128| Point::new(Rational::from_integer(x), Rational::from_integer(y));
+        let square = |k: i128| {
+            vec![
+                HalfPlane::new(q(-k, -k), q(k, -k)),
+                HalfPlane::new(q(k, -k), q(k, k)),
+                HalfPlane::new(q(k, k), q(-k, k)),
+                HalfPlane::new(q(-k, k), q(-k, -k)),
+            ]
+        };
+        assert_eq!(
+            half_plane_intersection(&square(1)),
+            vec![r(-1, -1), r(1, -1), r(1, 1), r(-1, 1)]
+        );
+        // x + y <= 1 で切ると三角形、同じ向きの半平面は内側だけが効く
+        let mut planes = square(1);
+        planes.push(HalfPlane::new(q(2, -1), q(-1, 2)));
+        planes.push(HalfPlane::new(q(3, -1), q(-1, 3)));
+        assert_eq!(
+            half_plane_intersection(&planes),
+            vec![r(-1, -1), r(1, -1), r(1, 0), r(0, 1), r(-1, 1)]
+        );
+        // 交点が格子点でない
+        let mut planes = square(2);
+        planes.push(HalfPlane::new(q(0, 0), q(3, 1)));
+        planes.push(HalfPlane::new(q(0, 1), q(-2, 0)));
+        assert_eq!(
+            half_plane_intersection(&planes),
+            vec![
+                Point::new(Rational::from_integer(-2), Rational::new(-2, 3)),
+                Point::new(Rational::from_integer(2), Rational::new(2, 3)),
+                r(2, 2),
+                r(-2, 0)
+            ]
+        );
+        // 空
+        let mut planes = square(1);
+        planes.push(HalfPlane::new(q(4, 5), q(5, 5)));
+        assert_eq!(half_plane_intersection(&planes), vec![]);
+        // 線分 (面積 0)
+        let mut planes = square(1);
+        planes.push(HalfPlane::new(q(0, 0), q(1, 0)));
+        planes.push(HalfPlane::new(q(0, 0), q(-1, 0)));
+        let result = half_plane_intersection(&planes);
+        assert!(result.len() < 3 || area2(&result) == Rational::from_integer(0));
+
+        // Sutherland–Hodgman で箱を切り取った結果と面積を比べる
+        let mut seed = 521_288_629_u64;
+        let big = 20;
+        let bx = vec![r(-big, -big), r(big, -big), r(big, big), r(-big, big)];
+        for _ in 0..300 {
+            let pts = random_points(&mut seed, 8, 10);
+            let mut planes = square(big);
+            let mut clipped = bx.clone();
+            for w in pts.chunks(2) {
+                if w[0] == w[1] {
+                    continue;
+                }
+                let h = HalfPlane::new(
+                    q(w[0].x as i128, w[0].y as i128),
+                    q(w[1].x as i128, w[1].y as i128),
+                );
+                clipped = clip(&clipped, &h);
+                planes.push(h);
+            }
+            let result = half_plane_intersection(&planes);
+            let zero = Rational::from_integer(0);
+            let expected = if clipped.len() < 3 {
+                zero
+            } else {
+                area2(&clipped)
+            };
+            if expected == zero {
+                assert!(result.len() < 3 || area2(&result) == zero);
+                continue;
+            }
+            assert_eq!(area2(&result), expected);
+            let n = result.len();
+            for i in 0..n {
+                assert_eq!(
+                    orientation(&result[i], &result[(i + 1) % n], &result[(i + 2) % n]),
+                    1
+                );
+            }
+        }
+    }
+}
@@ -13,29 +13,91 @@ pub trait Int:
     + Eq
     + PartialOrd
     + Ord
-    + Copy
+    + Clone
 {
     fn zero() -> Self;
     fn one() -> Self;
     fn next(self) -> Self;
     fn prev(self) -> Self;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn saturating_mul(self, other: Self) -> Self;
+    fn count_ones(self) -> u32;
+    fn trailing_zeros(self) -> u32;
+    /// 0 以下なら 0 を返す。途中計算でオーバーフローしない
     fn sqrt_floor(self) -> Self {
-        if self < Self::zero() {
+        if self <= Self::zero() {
             return Self::zero();
         }
+        // mid * mid <= self を mid <= self / mid で判定する
+        let two = Self::one().next();
+        let mut ok = Self::one();
+        let mut ng = self.clone();
+        while ok < ng {
+            let mid = ok.clone() + (ng.clone() - ok.clone() + Self::one()) / two.clone();
+            if mid <= self.clone() / mid.clone() {
+                ok = mid;
+            } else {
+                ng = mid.prev();
+            }
+        }
+        ok
+    }
+    /// x^k <= self を満たす最大の x (self >= 0, k >= 1)
+    /// 途中計算でオーバーフローしない
+    fn nth_root_floor(self, k: u32) -> Self {
+        assert!(k >= 1);
+        assert!(self >= Self::zero());
+        if k == 1 || self <= Self::one() {
+            return self;
+        }
+        let le = |x: &Self| {
+            let mut acc = Self::one();
+            for _ in 0..k {
+                acc = match acc.checked_mul(x.clone()) {
+                    Some(y) if y <= self => y,
+                    _ => return false,
+                };
+            }
+            true
+        };
         let two = Self::one().next();
-        let mut ok = Self::zero();
-        let mut ng = self.next();
-        while ng - ok > Self::one() {
-            let mid = (ng + ok) / two;
-            if mid * mid <= self {
+        let mut ok = Self::one();
+        let mut ng = self.clone();
+        while ok < ng {
+            let mid = ok.clone() + (ng.clone() - ok.clone() + Self::one()) / two.clone();
+            if le(&mid) {
                 ok = mid;
             } else {
-                ng = mid;
+                ng = mid.prev();
             }
         }
         ok
     }
+    /// 繰り返し二乗法による self^exp (オーバーフローは検査しない)
+    fn pow(self, exp: u32) -> Self {
+        let mut ret = Self::one();
+        let mut base = self;
+        let mut exp = exp;
+        while exp > 0 {
+            if exp & 1 == 1 {
+                ret = ret * base.clone();
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = base.clone() * base;
+            }
+        }
+        ret
+    }
+    fn abs(self) -> Self {
+        if self < Self::zero() {
+            Self::zero() - self
+        } else {
+            self
+        }
+    }
     fn is_zero(&self) -> bool {
         *self == Self::zero()
     }
@@ -43,7 +105,7 @@ pub trait Int:
         *self == Self::one()
     }
     fn div_rem(&self, other: &Self) -> (Self, Self) {
-        ((*self) / (*other), (*self) % (*other))
+        (self.clone() / other.clone(), self.clone() % other.clone())
     }
     fn div_floor(&self, other: &Self) -> Self {
         // Algorithm from [Daan Leijen. _Division and Modulus for Computer Scientists_,
@@ -58,15 +120,26 @@ pub trait Int:
         }
     }
     fn mod_floor(&self, other: &Self) -> Self {
-        let r = *self % *other;
+        let r = self.clone() % other.clone();
         if (r > Self::zero() && *other < Self::zero())
             || (r < Self::zero() && *other > Self::zero())
         {
-            r + *other
+            r + other.clone()
         } else {
             r
         }
     }
+    /// 正の無限大方向への丸めを行う除算
+    fn ceil_div(&self, other: &Self) -> Self {
+        let (d, r) = self.div_rem(other);
+        if (r > Self::zero() && *other > Self::zero())
+            || (r < Self::zero() && *other < Self::zero())
+        {
+            d.next()
+        } else {
+            d
+        }
+    }
 }
 
 #[snippet("INT_TRAIT")]
@@ -87,6 +160,24 @@ macro_rules! impl_int_for_numerics {
                 fn prev(self) -> Self {
                     self - Self::one()
                 }
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+                fn checked_sub(self, other: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, other)
+                }
+                fn checked_mul(self, other: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, other)
+                }
+                fn saturating_mul(self, other: Self) -> Self {
+                    <$t>::saturating_mul(self, other)
+                }
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+                fn trailing_zeros(self) -> u32 {
+                    <$t>::trailing_zeros(self)
+                }
             }
         )*
     }
@@ -94,7 +185,7 @@ macro_rules! impl_int_for_numerics {
 
 #[snippet("INT_TRAIT")]
 #[snippet("INT")]
-impl_int_for_numerics!(u8 i8 u16 i16 u32 i32 u64 i64 usize isize);
+impl_int_for_numerics!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize);
 
 #[snippet("INT")]
 #[snippet("GCD")]
@@ -105,7 +196,8 @@ where
     if b == T::zero() {
         a
     } else {
-        gcd(b, a % b)
+        let r = a % b.clone();
+        gcd(b, r)
     }
 }
 
@@ -114,7 +206,86 @@ pub fn lcm<T>(a: T, b: T) -> T
 where
     T: Int,
 {
-    a / gcd(a, b) * b
+    a.clone() / gcd(a, b.clone()) * b
+}
+
+/// 拡張ユークリッドの互除法
+/// `a * x + b * y = g` (g = gcd(a, b)) を満たす `(g, x, y)` を返す
+/// `T` は符号付き整数であること
+#[snippet("INT")]
+#[snippet("EXT_GCD", include = "INT_TRAIT")]
+pub fn ext_gcd<T>(a: T, b: T) -> (T, T, T)
+where
+    T: Int,
+{
+    if b.is_zero() {
+        (a, T::one(), T::zero())
+    } else {
+        let (g, x, y) = ext_gcd(b.clone(), a.clone() % b.clone());
+        (g, y.clone(), x - (a / b) * y)
+    }
+}
+
+/// 中国剰余定理
+/// `x ≡ r_i (mod m_i)` をすべて満たす `x` を `(x mod lcm, lcm)` の形で返す
+/// 法は互いに素でなくてもよい。解が存在しなければ `None`
+/// 途中計算は i128 で行う。lcm が i64 に収まらない場合も `None` を返す
+#[snippet("INT")]
+#[snippet("CRT", include = "EXT_GCD")]
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut r = 0_i64;
+    let mut m = 1_i64;
+    for &(ri, mi) in congruences {
+        assert!(mi >= 1);
+        let ri = ri.rem_euclid(mi);
+        let (g, p, _) = ext_gcd(m, mi);
+        if (ri - r) % g != 0 {
+            return None;
+        }
+        let step = mi / g;
+        let t = ((ri - r) / g) as i128 * p as i128;
+        let t = t.rem_euclid(step as i128);
+        let next_m = m as i128 * step as i128;
+        if next_m > i64::MAX as i128 {
+            return None;
+        }
+        r = ((r as i128 + m as i128 * t) % next_m) as i64;
+        m = next_m as i64;
+    }
+    Some((r, m))
+}
+
+/// Garner のアルゴリズム
+/// 法が互いに素な連立合同式 `x ≡ r_i (mod m_i)` の最小非負解 `x` を `modulo` で割った余りを返す
+/// `x` 自体が i64 に収まらないほど大きくても計算できる
+/// Time complexity: O(n^2)
+#[snippet("INT")]
+#[snippet("GARNER", include = "EXT_GCD")]
+pub fn garner(congruences: &[(i64, i64)], modulo: i64) -> i64 {
+    let inv = |a: i64, m: i64| -> i64 {
+        let (_, x, _) = ext_gcd(a.rem_euclid(m), m);
+        x.rem_euclid(m)
+    };
+    let n = congruences.len();
+    // coeffs[i] = m_0 m_1 ... m_{i-1} mod m_i, constants[i] = (途中までの x) mod m_i
+    // 最後の要素は modulo に対するもの
+    let mut coeffs = vec![1_i64; n + 1];
+    let mut constants = vec![0_i64; n + 1];
+    let moduli: Vec<i64> = congruences
+        .iter()
+        .map(|&(_, m)| m)
+        .chain(std::iter::once(modulo))
+        .collect();
+    for (i, &(r, m)) in congruences.iter().enumerate() {
+        let t = ((r - constants[i]).rem_euclid(m) as i128 * inv(coeffs[i], m) as i128 % m as i128)
+            as i64;
+        for j in (i + 1)..=n {
+            let mj = moduli[j] as i128;
+            constants[j] = ((constants[j] as i128 + t as i128 * coeffs[j] as i128) % mj) as i64;
+            coeffs[j] = (coeffs[j] as i128 * m as i128 % mj) as i64;
+        }
+    }
+    constants[n]
 }
 
 #[snippet("INT")]
@@ -125,13 +296,14 @@ where
     let mut ret = Vec::new();
     let mut cur = T::one();
     loop {
-        if cur * cur > n {
+        let sq = cur.clone() * cur.clone();
+        if sq > n {
             break;
         }
-        if n % cur == T::zero() {
-            ret.push(cur);
-            if cur * cur != n {
-                ret.push(n / cur);
+        if n.clone() % cur.clone() == T::zero() {
+            ret.push(cur.clone());
+            if sq != n {
+                ret.push(n.clone() / cur.clone());
             }
         }
         cur = cur.next();
@@ -155,7 +327,7 @@ where
 
     // prepare `2, 3, 4, ..., this` sequence
     while t <= n {
-        deque.push_back(t);
+        deque.push_back(t.clone());
         t = t.next();
     }
 
@@ -163,18 +335,14 @@ where
         Some(x) => x,
         None => return ret,
     };
-    ret.push(p);
-    while p * p <= n {
-        deque = deque
-            .iter()
-            .filter(|&&x| x % p != T::zero())
-            .copied()
-            .collect();
+    ret.push(p.clone());
+    while p.clone() * p.clone() <= n {
+        deque.retain(|x| x.clone() % p.clone() != T::zero());
         p = match deque.pop_front() {
             Some(x) => x,
             None => return ret,
         };
-        ret.push(p);
+        ret.push(p.clone());
     }
     for n in deque {
         ret.push(n);
@@ -195,19 +363,19 @@ where
     let mut n = n;
     let mut cur = T::one().next(); // 2
     loop {
-        if cur * cur > n {
+        if cur.clone() * cur.clone() > n {
             break;
         }
-        if n % cur != T::zero() {
+        if n.clone() % cur.clone() != T::zero() {
             cur = cur.next();
             continue;
         }
         let mut exp = 0;
-        while n % cur == T::zero() {
+        while n.clone() % cur.clone() == T::zero() {
             exp += 1;
-            n = n / cur;
+            n = n / cur.clone();
         }
-        ret.insert(cur, exp);
+        ret.insert(cur.clone(), exp);
     }
     if n != T::one() {
         ret.insert(n, 1);
@@ -215,6 +383,171 @@ where
     ret
 }
 
+#[snippet("INT")]
+#[snippet("MILLER_RABIN")]
+fn mul_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+#[snippet("INT")]
+#[snippet("MILLER_RABIN")]
+fn pow_mod_u64(a: u64, e: u64, m: u64) -> u64 {
+    let mut ret = 1 % m;
+    let mut a = a % m;
+    let mut e = e;
+    while e > 0 {
+        if e & 1 == 1 {
+            ret = mul_mod_u64(ret, a, m);
+        }
+        a = mul_mod_u64(a, a, m);
+        e >>= 1;
+    }
+    ret
+}
+
+/// 決定的ミラー・ラビン素数判定
+/// 底を 2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37 とすれば 2^64 未満で誤判定しない
+/// Time complexity: O(log n)
+#[snippet("INT")]
+#[snippet("MILLER_RABIN")]
+pub fn is_prime(n: u64) -> bool {
+    const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+    if n < 2 {
+        return false;
+    }
+    for &p in BASES.iter() {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'outer: for &a in BASES.iter() {
+        let mut x = pow_mod_u64(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod_u64(x, x, n);
+            if x == n - 1 {
+                continue 'outer;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// ポラード・ロー法で n の非自明な約数をひとつ見つける (n は奇数の合成数)
+#[snippet("INT")]
+#[snippet(
+    "POLLARD_RHO",
+    include = "MILLER_RABIN",
+    include = "GCD",
+    include = "INT_TRAIT"
+)]
+fn pollard_rho(n: u64) -> u64 {
+    // Brent の変種: gcd の計算を M 回分まとめる
+    const M: usize = 128;
+    for c in 1.. {
+        let f = |x: u64| (mul_mod_u64(x, x, n) + c) % n;
+        let mut y = 2_u64;
+        let mut g = 1;
+        let mut q = 1;
+        let mut x = y;
+        let mut ys = y;
+        let mut r = 1;
+        while g == 1 {
+            x = y;
+            for _ in 0..r {
+                y = f(y);
+            }
+            let mut k = 0;
+            while k < r && g == 1 {
+                ys = y;
+                for _ in 0..M.min(r - k) {
+                    y = f(y);
+                    q = mul_mod_u64(q, x.max(y) - x.min(y), n);
+                }
+                g = gcd(q, n);
+                k += M;
+            }
+            r <<= 1;
+        }
+        if g == n {
+            // まとめすぎて n になったら 1 ステップずつやり直す
+            loop {
+                ys = f(ys);
+                g = gcd(x.max(ys) - x.min(ys), n);
+                if g != 1 {
+                    break;
+                }
+            }
+        }
+        if g != n {
+            return g;
+        }
+    }
+    unreachable!()
+}
+
+/// ポラード・ロー法による素因数分解
+/// 戻り値は `factorize` と同じく、素因数 => 指数 の HashMap (n <= 1 なら空)
+/// 内部では u64 で計算するので、n は u64 に収まること
+/// Time complexity: O(n^(1/4)) (expected)
+#[snippet("INT")]
+#[snippet("POLLARD_RHO")]
+pub fn factorize_fast<T>(n: T) -> std::collections::HashMap<T, usize>
+where
+    T: Int + std::convert::TryInto<u64> + std::convert::TryFrom<u64>,
+{
+    if n <= T::one() {
+        return std::collections::HashMap::new();
+    }
+    let n: u64 = match n.try_into() {
+        Ok(n) => n,
+        Err(_) => panic!("factorize_fast: n must fit in u64"),
+    };
+    factorize_fast_u64(n)
+        .into_iter()
+        .map(|(p, e)| (T::try_from(p).ok().unwrap(), e))
+        .collect()
+}
+
+#[snippet("INT")]
+#[snippet("POLLARD_RHO")]
+fn factorize_fast_u64(n: u64) -> std::collections::HashMap<u64, usize> {
+    fn rec(n: u64, ret: &mut std::collections::HashMap<u64, usize>) {
+        if n == 1 {
+            return;
+        }
+        if is_prime(n) {
+            *ret.entry(n).or_insert(0) += 1;
+            return;
+        }
+        let d = pollard_rho(n);
+        rec(d, ret);
+        rec(n / d, ret);
+    }
+    let mut ret = std::collections::HashMap::new();
+    if n <= 1 {
+        return ret;
+    }
+    let mut n = n;
+    for &p in [2_u64, 3, 5, 7].iter() {
+        let mut exp = 0;
+        while n.is_multiple_of(p) {
+            exp += 1;
+            n /= p;
+        }
+        if exp > 0 {
+            ret.insert(p, exp);
+        }
+    }
+    rec(n, &mut ret);
+    ret
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,6 +571,53 @@ mod tests {
         for test in tests.iter() {
             assert_eq!(test.0.sqrt_floor(), test.1);
         }
+        assert_eq!(u64::MAX.sqrt_floor(), 4_294_967_295);
+        assert_eq!(i64::MAX.sqrt_floor(), 3_037_000_499);
+        assert_eq!(u128::MAX.sqrt_floor(), u64::MAX as u128);
+        assert_eq!(i128::MAX.sqrt_floor(), 13_043_817_825_332_782_212);
+        assert_eq!(u8::MAX.sqrt_floor(), 15);
+        assert_eq!(999_999_999_999_999_999_u64.sqrt_floor(), 999_999_999);
+        assert_eq!(1_000_000_000_000_000_000_u64.sqrt_floor(), 1_000_000_000);
+    }
+
+    #[test]
+    fn test_nth_root_floor() {
+        for n in 0..2000_u64 {
+            for k in 1..6 {
+                let x = n.nth_root_floor(k);
+                assert!(x.pow(k) <= n && (x + 1).pow(k) > n);
+            }
+        }
+        assert_eq!(u64::MAX.nth_root_floor(2), 4_294_967_295);
+        assert_eq!(u64::MAX.nth_root_floor(3), 2_642_245);
+        assert_eq!(u64::MAX.nth_root_floor(64), 1);
+        assert_eq!(i128::MAX.nth_root_floor(3), 5_541_191_377_756);
+        assert_eq!(1_000_000_000_000_000_000_i64.nth_root_floor(6), 1000);
+    }
+
+    #[test]
+    fn test_int_ops() {
+        fn generic_pow<T: Int>(x: T, e: u32) -> T {
+            Int::pow(x, e)
+        }
+        assert_eq!(generic_pow(3_i64, 0), 1);
+        assert_eq!(generic_pow(-3_i64, 5), -243);
+        assert_eq!(generic_pow(10_u128, 30), 10_u128.pow(30));
+        assert_eq!(Int::abs(-5_i32), 5);
+        assert_eq!(Int::abs(5_u32), 5);
+        assert_eq!(Int::checked_mul(1_u64 << 32, 1 << 32), None);
+        assert_eq!(Int::checked_mul(1_i128 << 64, 1 << 62), Some(1 << 126));
+        assert_eq!(Int::saturating_mul(i64::MIN, 2), i64::MIN);
+        assert_eq!(Int::count_ones(0b1011_u8), 3);
+        assert_eq!(Int::trailing_zeros(96_i64), 5);
+        for a in -20..20_i64 {
+            for b in (-5..5).filter(|&b| b != 0) {
+                let expected = (a as f64 / b as f64).ceil() as i64;
+                assert_eq!(a.ceil_div(&b), expected);
+            }
+        }
+        assert_eq!(7_u64.ceil_div(&2), 4);
+        assert_eq!(6_u64.ceil_div(&2), 3);
     }
 
     #[test]
@@ -252,6 +632,47 @@ mod tests {
         assert_eq!(lcm(14, 21), 42);
     }
 
+    #[test]
+    fn test_ext_gcd() {
+        assert_eq!(ext_gcd(3, 7), (1, -2, 1));
+        assert_eq!(ext_gcd(10, 4), (2, 1, -2));
+        for a in 1..30_i64 {
+            for b in 1..30_i64 {
+                let (g, x, y) = ext_gcd(a, b);
+                assert_eq!(g, gcd(a, b));
+                assert_eq!(a * x + b * y, g);
+            }
+        }
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        // lcm close to i64::MAX
+        let p = 1_000_000_007;
+        let q = 998_244_353;
+        let x = 123_456_789_012_345_678_i64 % (p * q);
+        assert_eq!(crt(&[(x % p, p), (x % q, q)]), Some((x, p * q)));
+        // lcm = 10^18 * 11 does not fit in i64
+        assert_eq!(crt(&[(0, 1_000_000_000_000_000_000), (0, 11)]), None);
+        assert_eq!(crt(&[(1, 1 << 62), (1, 3)]), None);
+    }
+
+    #[test]
+    fn test_garner() {
+        assert_eq!(garner(&[(2, 3), (3, 5), (2, 7)], 1_000_000_007), 23);
+        assert_eq!(garner(&[(2, 3), (3, 5), (2, 7)], 10), 3);
+        // x = 10^18 + 9 is larger than the target modulo
+        let x = 1_000_000_000_000_000_009_i64;
+        let moduli = [1_000_000_007_i64, 998_244_353, 754_974_721];
+        let congruences: Vec<(i64, i64)> = moduli.iter().map(|&m| (x % m, m)).collect();
+        assert_eq!(garner(&congruences, 1_000_000_009), x % 1_000_000_009);
+    }
+
     #[test]
     fn test_divisors() {
         assert_eq!(divosors(12), vec![1, 2, 3, 4, 6, 12]);
@@ -306,4 +727,53 @@ mod tests {
         };
         assert_eq!(factorize(2013_usize), result_2013);
     }
+
+    #[test]
+    fn test_is_prime() {
+        let primes = lower_primes(10000_u64);
+        for n in 0..=10000_u64 {
+            assert_eq!(is_prime(n), primes.binary_search(&n).is_ok());
+        }
+        assert!(is_prime(998_244_353));
+        assert!(is_prime(1_000_000_007));
+        assert!(is_prime(18_446_744_073_709_551_557)); // largest prime below 2^64
+        assert!(!is_prime(1_000_000_007 * 998_244_353));
+        assert!(!is_prime(3_215_031_751)); // strong pseudoprime to bases 2, 3, 5, 7
+        assert!(!is_prime(u64::MAX));
+    }
+
+    #[test]
+    fn test_factorize_fast() {
+        for n in 0..2000_u64 {
+            assert_eq!(factorize_fast(n), factorize(n));
+        }
+        let result = hashmap! {
+            998_244_353_u64 => 1_usize,
+            1_000_000_007 => 1,
+        };
+        assert_eq!(factorize_fast(998_244_353 * 1_000_000_007), result);
+        let result = hashmap! {
+            2_u64 => 10_usize,
+            999_999_937 => 1,
+            3 => 2,
+        };
+        assert_eq!(factorize_fast(1024 * 9 * 999_999_937), result);
+        let result = hashmap! {
+            1_000_000_007_u64 => 2_usize,
+        };
+        assert_eq!(factorize_fast(1_000_000_007 * 1_000_000_007), result);
+        let n = 999_999_999_999_999_989_u64;
+        assert_eq!(factorize_fast(n), hashmap! { n => 1_usize });
+        let n = 1_000_000_000_000_000_000_u64;
+        assert_eq!(factorize_fast(n), hashmap! { 2_u64 => 18_usize, 5 => 18 });
+        // factorize と同じ型で使える
+        for n in -5..2000_i64 {
+            assert_eq!(factorize_fast(n), factorize(n));
+        }
+        assert_eq!(factorize_fast(360_usize), factorize(360_usize));
+        assert_eq!(
+            factorize_fast(4_294_967_291_u128 * 65_521),
+            hashmap! { 4_294_967_291_u128 => 1_usize, 65_521 => 1 }
+        );
+    }
 }
@@ -0,0 +1,133 @@
+use crate::combination::Comb;
+use crate::modulo::ModInt;
+use cargo_snippet::snippet;
+
+/// ラグランジュ補間 (標本点が 0, 1, ..., d のとき)
+/// `ys[i] = f(i)` を満たす d 次以下の多項式 f について f(x) を求める
+/// `comb` は d + 1 以上の階乗テーブルを持っていること
+/// Time complexity: O(d)
+#[snippet("LAGRANGE", include = "COMBINATION")]
+pub fn lagrange_interpolation_consecutive(ys: &[ModInt], x: u64, comb: &Comb) -> ModInt {
+    assert!(!ys.is_empty());
+    let n = ys.len();
+    if x < n as u64 {
+        return ys[x as usize];
+    }
+    let modulo = ys[0].modulo();
+    let x = ModInt::new((x % modulo as u64) as i64, modulo);
+    // left[i] = (x - 0) (x - 1) ... (x - (i - 1))
+    // right[i] = (x - (i + 1)) ... (x - (n - 1))
+    let mut left = vec![ModInt::new(1, modulo); n + 1];
+    for i in 0..n {
+        left[i + 1] = left[i] * (x - i);
+    }
+    let mut right = vec![ModInt::new(1, modulo); n + 1];
+    for i in (0..n).rev() {
+        right[i] = right[i + 1] * (x - i);
+    }
+    let d = n - 1;
+    ys.iter()
+        .enumerate()
+        .fold(ModInt::new(0, modulo), |acc, (i, &y)| {
+            let term = y * left[i] * right[i + 1] * comb.inv_fact(i) * comb.inv_fact(d - i);
+            if (d - i).is_multiple_of(2) {
+                acc + term
+            } else {
+                acc - term
+            }
+        })
+}
+
+/// ラグランジュ補間 (標本点が任意のとき)
+/// `ys[i] = f(xs[i])` を満たす d 次以下の多項式 f について f(x) を求める
+/// `xs` の要素は相異なること
+/// Time complexity: O(d^2)
+#[snippet("LAGRANGE")]
+pub fn lagrange_interpolation(xs: &[ModInt], ys: &[ModInt], x: ModInt) -> ModInt {
+    assert_eq!(xs.len(), ys.len());
+    let modulo = x.modulo();
+    let mut ret = ModInt::new(0, modulo);
+    for (i, (&xi, &yi)) in xs.iter().zip(ys.iter()).enumerate() {
+        let mut numer = ModInt::new(1, modulo);
+        let mut denom = ModInt::new(1, modulo);
+        for (j, &xj) in xs.iter().enumerate() {
+            if i != j {
+                numer *= x - xj;
+                denom *= xi - xj;
+            }
+        }
+        ret += yi * numer / denom;
+    }
+    ret
+}
+
+/// べき乗和 1^k + 2^k + ... + n^k
+/// k + 1 次多項式になるので、k + 2 点から補間する
+/// Time complexity: O(k log k)
+#[snippet("LAGRANGE")]
+pub fn sum_of_powers(n: u64, k: usize, modulo: i64) -> ModInt {
+    let comb = Comb::new(k + 2, modulo);
+    let mut ys = Vec::with_capacity(k + 2);
+    let mut acc = ModInt::new(0, modulo);
+    ys.push(acc);
+    for i in 1..(k + 2) {
+        acc += ModInt::new(i as i64, modulo).pow(k as u64);
+        ys.push(acc);
+    }
+    lagrange_interpolation_consecutive(&ys, n, &comb)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modulo::IntoModInt;
+
+    #[test]
+    fn test_lagrange_interpolation_consecutive() {
+        // f(x) = x^3 - 2x + 5
+        let f = |x: i64| x * x * x - 2 * x + 5;
+        let ys: Vec<ModInt> = (0..4).map(|x| f(x).mint()).collect();
+        let comb = Comb::new(10, 1_000_000_007);
+        for x in 0..50 {
+            assert_eq!(
+                lagrange_interpolation_consecutive(&ys, x as u64, &comb),
+                f(x).mint()
+            );
+        }
+        let x = 1_000_000_000_000_u64;
+        let xm = x.mint();
+        assert_eq!(
+            lagrange_interpolation_consecutive(&ys, x, &comb),
+            xm * xm * xm - xm * 2 + 5
+        );
+    }
+
+    #[test]
+    fn test_lagrange_interpolation() {
+        // f(x) = 2x^2 + 3x + 1
+        let f = |x: i64| 2 * x * x + 3 * x + 1;
+        let xs: Vec<ModInt> = [-3_i64, 5, 10].iter().map(|x| x.mint()).collect();
+        let ys: Vec<ModInt> = [-3_i64, 5, 10].iter().map(|&x| f(x).mint()).collect();
+        for x in -20..20 {
+            assert_eq!(lagrange_interpolation(&xs, &ys, x.mint()), f(x).mint());
+        }
+    }
+
+    #[test]
+    fn test_sum_of_powers() {
+        let modulo = 1_000_000_007;
+        for k in 0..6 {
+            let mut acc = 0_i64;
+            for n in 0..30_u64 {
+                if n > 0 {
+                    acc += (n as i64).pow(k as u32);
+                }
+                assert_eq!(sum_of_powers(n, k, modulo), acc % modulo);
+            }
+        }
+        // 1 + 2 + ... + 10^12 = 10^12 (10^12 + 1) / 2
+        let n = 1_000_000_000_000_u64;
+        let expected = (n as u128 * (n as u128 + 1) / 2 % modulo as u128) as i64;
+        assert_eq!(sum_of_powers(n, 1, modulo), expected);
+    }
+}
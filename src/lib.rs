@@ -1,30 +1,63 @@
 #![feature(custom_inner_attributes)]
 #![feature(proc_macro_hygiene)]
 
+mod bigint;
 mod binary_search_ext;
+mod bitset;
 mod combination;
+mod continued_fraction;
+mod convolution;
+mod counting;
+mod floor_sum;
+mod formal_power_series;
+mod geometry;
 mod input;
 mod integer;
+mod interpolation;
+mod linear_recurrence;
+mod matrix;
 mod modulo;
+mod multiplicative;
 mod permutations;
+mod prime_counting;
 mod rational;
 mod read;
 mod rolling_hash;
 mod segment_tree;
+mod sieve;
 mod template;
 mod two_dimension_vector_ext;
 mod union_find;
+mod xor_basis;
 
+pub use bigint::*;
 pub use binary_search_ext::*;
+pub use bitset::*;
 pub use combination::*;
+pub use continued_fraction::*;
+pub use convolution::*;
+pub use counting::*;
+pub use floor_sum::*;
+pub use formal_power_series::*;
+pub use geometry::*;
+// input, template は #[macro_export] のマクロのみを持つ
+#[allow(unused_imports)]
 pub use input::*;
 pub use integer::*;
+pub use interpolation::*;
+pub use linear_recurrence::*;
+pub use matrix::*;
 pub use modulo::*;
+pub use multiplicative::*;
 pub use permutations::*;
+pub use prime_counting::*;
 pub use rational::*;
 pub use read::*;
 pub use rolling_hash::*;
 pub use segment_tree::*;
+pub use sieve::*;
+#[allow(unused_imports)]
 pub use template::*;
 pub use two_dimension_vector_ext::*;
 pub use union_find::*;
+pub use xor_basis::*;
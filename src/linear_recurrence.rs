@@ -0,0 +1,168 @@
+use crate::formal_power_series::FormalPowerSeries;
+use crate::modulo::ModInt;
+use cargo_snippet::snippet;
+
+/// Berlekamp–Massey 法
+/// 数列 `s` を生成する最短の線形漸化式 `s[i] = c[0] s[i - 1] + c[1] s[i - 2] + ... + c[d - 1] s[i - d]`
+/// の係数 `c` を求める
+/// 次数 d の漸化式を復元するには、最初の 2d 項が必要
+/// Time complexity: O(n^2)
+#[snippet("LINEAR_RECURRENCE", include = "FPS")]
+pub fn berlekamp_massey(s: &[ModInt]) -> Vec<ModInt> {
+    if s.is_empty() {
+        return Vec::new();
+    }
+    let modulo = s[0].modulo();
+    let zero = ModInt::new(0, modulo);
+    // c(x) = 1 - c_1 x - c_2 x^2 - ... - c_l x^l の形で持つ
+    let mut cur = vec![ModInt::new(1, modulo)];
+    let mut prev = vec![ModInt::new(1, modulo)];
+    let mut len = 0;
+    let mut prev_delta = ModInt::new(1, modulo);
+    let mut shift = 1;
+    for i in 0..s.len() {
+        let delta = (0..=len).fold(zero, |acc, j| acc + cur[j] * s[i - j]);
+        if delta.value() == 0 {
+            shift += 1;
+            continue;
+        }
+        let coef = delta / prev_delta;
+        let mut next = cur.clone();
+        next.resize(cur.len().max(prev.len() + shift), zero);
+        for (j, &p) in prev.iter().enumerate() {
+            next[j + shift] -= coef * p;
+        }
+        if 2 * len <= i {
+            prev = cur;
+            prev_delta = delta;
+            len = i + 1 - len;
+            shift = 1;
+        } else {
+            shift += 1;
+        }
+        cur = next;
+        cur.resize(cur.len().max(len + 1), zero);
+    }
+    cur.truncate(len + 1);
+    cur.iter().skip(1).map(|&c| -c).collect()
+}
+
+/// Bostan–Mori 法
+/// 有理式 `p / q` の x^n の係数を求める (`q` の定数項は 0 でないこと)
+/// Time complexity: O(d log d log n)  (d = deg q)
+#[snippet("LINEAR_RECURRENCE")]
+pub fn bostan_mori(p: &FormalPowerSeries, q: &FormalPowerSeries, n: u64) -> ModInt {
+    let modulo = q.modulo();
+    let mut p = p.clone();
+    let mut q = q.clone();
+    let mut n = n;
+    while n > 0 {
+        let q_neg = FormalPowerSeries::new(
+            q.coef()
+                .iter()
+                .enumerate()
+                .map(|(i, &x)| if i % 2 == 0 { x } else { -x })
+                .collect(),
+            modulo,
+        );
+        let u = &p * &q_neg;
+        let v = &q * &q_neg;
+        let parity = (n % 2) as usize;
+        p = FormalPowerSeries::new(
+            u.coef().iter().skip(parity).step_by(2).copied().collect(),
+            modulo,
+        );
+        q = FormalPowerSeries::new(v.coef().iter().step_by(2).copied().collect(), modulo);
+        n >>= 1;
+    }
+    p.get(0) / q.get(0)
+}
+
+/// 線形漸化式 `a[i] = c[0] a[i - 1] + ... + c[d - 1] a[i - d]` で定まる数列の第 n 項 (0-indexed)
+/// `a` には最初の d 項を与える。mod を決めるため `a` は空であってはならない
+#[snippet("LINEAR_RECURRENCE")]
+pub fn linear_recurrence_nth(a: &[ModInt], c: &[ModInt], n: u64) -> ModInt {
+    assert!(!a.is_empty(), "a must contain at least one term");
+    assert!(a.len() >= c.len());
+    if n < a.len() as u64 {
+        return a[n as usize];
+    }
+    let modulo = a[0].modulo();
+    let d = c.len();
+    let mut q_coef = Vec::with_capacity(d + 1);
+    q_coef.push(ModInt::new(1, modulo));
+    q_coef.extend(c.iter().map(|&x| -x));
+    let q = FormalPowerSeries::new(q_coef, modulo);
+    let p = (&FormalPowerSeries::new(a[..d].to_vec(), modulo) * &q).prefix(d);
+    bostan_mori(&p, &q, n)
+}
+
+/// 数列の最初の数項から漸化式を推定し、第 n 項 (0-indexed) を求める
+#[snippet("LINEAR_RECURRENCE")]
+pub fn guess_nth_term(a: &[ModInt], n: u64) -> ModInt {
+    let c = berlekamp_massey(a);
+    linear_recurrence_nth(a, &c, n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modulo::IntoModInt;
+
+    fn mints(v: &[i64]) -> Vec<ModInt> {
+        v.iter().map(|x| x.mint()).collect()
+    }
+
+    #[test]
+    fn test_berlekamp_massey() {
+        // Fibonacci
+        let c = berlekamp_massey(&mints(&[0, 1, 1, 2, 3, 5, 8, 13]));
+        assert_eq!(c, mints(&[1, 1]));
+
+        // a[i] = 2 a[i - 1] - a[i - 3]
+        let mut a = vec![1, 2, 4];
+        for i in 3..12 {
+            a.push(2 * a[i - 1] - a[i - 3]);
+        }
+        assert_eq!(berlekamp_massey(&mints(&a)), mints(&[2, 0, -1]));
+
+        assert!(berlekamp_massey(&mints(&[0, 0, 0])).is_empty());
+        assert_eq!(berlekamp_massey(&mints(&[5, 5, 5, 5])), mints(&[1]));
+    }
+
+    #[test]
+    fn test_linear_recurrence_nth() {
+        let fib = mints(&[0, 1]);
+        let c = mints(&[1, 1]);
+        assert_eq!(linear_recurrence_nth(&fib, &c, 0), 0);
+        assert_eq!(linear_recurrence_nth(&fib, &c, 10), 55);
+        assert_eq!(
+            linear_recurrence_nth(&fib, &c, 90),
+            2_880_067_194_370_816_120_i64 % 1_000_000_007
+        );
+        // F(10^18) mod 10^9 + 7
+        assert_eq!(
+            linear_recurrence_nth(&fib, &c, 1_000_000_000_000_000_000),
+            209_783_453
+        );
+        // d = 0 の漸化式は a[i] = 0 (i >= 1)
+        assert_eq!(linear_recurrence_nth(&mints(&[5]), &[], 0), 5);
+        assert_eq!(linear_recurrence_nth(&mints(&[5]), &[], 7), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "a must contain at least one term")]
+    fn test_linear_recurrence_nth_empty() {
+        linear_recurrence_nth(&[], &[], 3);
+    }
+
+    #[test]
+    fn test_guess_nth_term() {
+        let mut a = vec![1_i64, 1, 1];
+        for i in 3..20 {
+            a.push((a[i - 1] + 2 * a[i - 2] + 3 * a[i - 3]) % 1_000_000_007);
+        }
+        let expected = a[19];
+        assert_eq!(guess_nth_term(&mints(&a[..10]), 19), expected);
+    }
+}
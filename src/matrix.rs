@@ -0,0 +1,420 @@
+use crate::modulo::ModInt;
+use cargo_snippet::snippet;
+
+/// 半環
+/// `ModInt` のように零元・単位元が値ごとの情報 (mod など) に依存しうるので、
+/// 既存の値から同じ構造の零元・単位元を作れるようにしている
+#[snippet("MATRIX", include = "MOD_INT")]
+pub trait Semiring: Copy {
+    /// `self` と同じ構造における加法の単位元
+    fn zero_like(&self) -> Self;
+    /// `self` と同じ構造における乗法の単位元
+    fn one_like(&self) -> Self;
+    fn plus(self, rhs: Self) -> Self;
+    fn times(self, rhs: Self) -> Self;
+}
+
+#[snippet("MATRIX")]
+impl Semiring for ModInt {
+    fn zero_like(&self) -> Self {
+        ModInt::new(0, self.modulo())
+    }
+    fn one_like(&self) -> Self {
+        ModInt::new(1, self.modulo())
+    }
+    fn plus(self, rhs: Self) -> Self {
+        self + rhs
+    }
+    fn times(self, rhs: Self) -> Self {
+        self * rhs
+    }
+}
+
+#[snippet("MATRIX")]
+impl Semiring for i64 {
+    fn zero_like(&self) -> Self {
+        0
+    }
+    fn one_like(&self) -> Self {
+        1
+    }
+    fn plus(self, rhs: Self) -> Self {
+        self + rhs
+    }
+    fn times(self, rhs: Self) -> Self {
+        self * rhs
+    }
+}
+
+/// (max, +) 半環
+/// `i64::MIN` を -∞ として扱う
+#[snippet("MATRIX")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MaxPlus(pub i64);
+
+#[snippet("MATRIX")]
+impl Semiring for MaxPlus {
+    fn zero_like(&self) -> Self {
+        MaxPlus(i64::MIN)
+    }
+    fn one_like(&self) -> Self {
+        MaxPlus(0)
+    }
+    fn plus(self, rhs: Self) -> Self {
+        MaxPlus(std::cmp::max(self.0, rhs.0))
+    }
+    fn times(self, rhs: Self) -> Self {
+        if self.0 == i64::MIN || rhs.0 == i64::MIN {
+            MaxPlus(i64::MIN)
+        } else {
+            MaxPlus(self.0 + rhs.0)
+        }
+    }
+}
+
+/// (min, +) 半環
+/// `i64::MAX` を +∞ として扱う
+#[snippet("MATRIX")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MinPlus(pub i64);
+
+#[snippet("MATRIX")]
+impl Semiring for MinPlus {
+    fn zero_like(&self) -> Self {
+        MinPlus(i64::MAX)
+    }
+    fn one_like(&self) -> Self {
+        MinPlus(0)
+    }
+    fn plus(self, rhs: Self) -> Self {
+        MinPlus(std::cmp::min(self.0, rhs.0))
+    }
+    fn times(self, rhs: Self) -> Self {
+        if self.0 == i64::MAX || rhs.0 == i64::MAX {
+            MinPlus(i64::MAX)
+        } else {
+            MinPlus(self.0 + rhs.0)
+        }
+    }
+}
+
+/// 半環上の行列
+/// 零元・単位元を既存の要素から作るため、0 行・0 列の行列は扱わない
+#[snippet("MATRIX")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<Vec<T>>,
+}
+
+#[snippet("MATRIX")]
+impl<T> Matrix<T>
+where
+    T: Semiring,
+{
+    pub fn new(data: Vec<Vec<T>>) -> Self {
+        let rows = data.len();
+        assert!(rows > 0, "matrix must have at least one row");
+        let cols = data[0].len();
+        assert!(cols > 0, "matrix must have at least one column");
+        assert!(data.iter().all(|row| row.len() == cols));
+        Self { rows, cols, data }
+    }
+
+    pub fn filled(rows: usize, cols: usize, value: T) -> Self {
+        Self::new(vec![vec![value; cols]; rows])
+    }
+
+    /// n 次の単位行列 (n >= 1)
+    /// `unit` は零元・単位元を作るためのサンプル (`ModInt` なら mod を決めるのに使う)
+    pub fn identity(n: usize, unit: T) -> Self {
+        assert!(n > 0, "matrix must have at least one row");
+        let mut data = vec![vec![unit.zero_like(); n]; n];
+        for (i, row) in data.iter_mut().enumerate() {
+            row[i] = unit.one_like();
+        }
+        Self::new(data)
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn transpose(&self) -> Self {
+        let data = (0..self.cols)
+            .map(|j| (0..self.rows).map(|i| self.data[i][j]).collect())
+            .collect();
+        Self::new(data)
+    }
+
+    /// 行列累乗
+    /// Time complexity: O(n^3 log e)
+    pub fn pow(&self, e: u64) -> Self {
+        assert_eq!(self.rows, self.cols);
+        let mut res = Self::identity(self.rows, self.data[0][0]);
+        let mut x = self.clone();
+        let mut e = e;
+        while e > 0 {
+            if e & 1 == 1 {
+                res = &res * &x;
+            }
+            x = &x * &x;
+            e >>= 1;
+        }
+        res
+    }
+}
+
+#[snippet("MATRIX")]
+impl<T> std::ops::Index<usize> for Matrix<T> {
+    type Output = [T];
+
+    fn index(&self, i: usize) -> &[T] {
+        &self.data[i]
+    }
+}
+
+#[snippet("MATRIX")]
+impl<T> std::ops::IndexMut<usize> for Matrix<T> {
+    fn index_mut(&mut self, i: usize) -> &mut [T] {
+        &mut self.data[i]
+    }
+}
+
+#[snippet("MATRIX")]
+impl<'a, T> std::ops::Mul<&'a Matrix<T>> for &'a Matrix<T>
+where
+    T: Semiring,
+{
+    type Output = Matrix<T>;
+
+    fn mul(self, other: &Matrix<T>) -> Matrix<T> {
+        assert_eq!(self.cols, other.rows);
+        let zero = self.data[0][0].zero_like();
+        let mut data = vec![vec![zero; other.cols]; self.rows];
+        for (i, row) in data.iter_mut().enumerate() {
+            for k in 0..self.cols {
+                let a = self.data[i][k];
+                for (j, x) in row.iter_mut().enumerate() {
+                    *x = x.plus(a.times(other.data[k][j]));
+                }
+            }
+        }
+        Matrix::new(data)
+    }
+}
+
+#[snippet("MATRIX")]
+impl<T> std::ops::Mul for Matrix<T>
+where
+    T: Semiring,
+{
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        &self * &other
+    }
+}
+
+/// 体 (mod は素数) 上の行列演算
+#[snippet("MATRIX")]
+impl Matrix<ModInt> {
+    /// 行列式
+    /// Time complexity: O(n^3)
+    pub fn determinant(&self) -> ModInt {
+        assert_eq!(self.rows, self.cols);
+        let n = self.rows;
+        let mut a = self.data.clone();
+        let mut det = self.data[0][0].one_like();
+        for col in 0..n {
+            let pivot = match (col..n).find(|&r| a[r][col].value() != 0) {
+                Some(r) => r,
+                None => return self.data[0][0].zero_like(),
+            };
+            if pivot != col {
+                a.swap(pivot, col);
+                det = -det;
+            }
+            det *= a[col][col];
+            let inv = a[col][col].inv();
+            let pivot_row = a[col].clone();
+            for row in a.iter_mut().skip(col + 1) {
+                let factor = row[col] * inv;
+                if factor.value() == 0 {
+                    continue;
+                }
+                for (x, &p) in row.iter_mut().zip(pivot_row.iter()).skip(col) {
+                    *x -= p * factor;
+                }
+            }
+        }
+        det
+    }
+
+    /// 逆行列
+    /// 正則でなければ `None`
+    /// Time complexity: O(n^3)
+    pub fn inverse(&self) -> Option<Self> {
+        assert_eq!(self.rows, self.cols);
+        let n = self.rows;
+        let mut a = self.data.clone();
+        let mut inv = Self::identity(n, self.data[0][0]).data;
+        for col in 0..n {
+            let pivot = (col..n).find(|&r| a[r][col].value() != 0)?;
+            a.swap(pivot, col);
+            inv.swap(pivot, col);
+            let p_inv = a[col][col].inv();
+            for c in 0..n {
+                a[col][c] *= p_inv;
+                inv[col][c] *= p_inv;
+            }
+            for r in 0..n {
+                if r == col || a[r][col].value() == 0 {
+                    continue;
+                }
+                let factor = a[r][col];
+                for c in 0..n {
+                    let sub_a = a[col][c] * factor;
+                    let sub_inv = inv[col][c] * factor;
+                    a[r][c] -= sub_a;
+                    inv[r][c] -= sub_inv;
+                }
+            }
+        }
+        Some(Self::new(inv))
+    }
+
+    /// 階数
+    /// Time complexity: O(n m min(n, m))
+    pub fn rank(&self) -> usize {
+        let mut a = self.data.clone();
+        let mut rank = 0;
+        for col in 0..self.cols {
+            let pivot = match (rank..self.rows).find(|&r| a[r][col].value() != 0) {
+                Some(r) => r,
+                None => continue,
+            };
+            a.swap(pivot, rank);
+            let inv = a[rank][col].inv();
+            let pivot_row = a[rank].clone();
+            for row in a.iter_mut().skip(rank + 1) {
+                let factor = row[col] * inv;
+                if factor.value() == 0 {
+                    continue;
+                }
+                for (x, &p) in row.iter_mut().zip(pivot_row.iter()).skip(col) {
+                    *x -= p * factor;
+                }
+            }
+            rank += 1;
+        }
+        rank
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modulo::IntoModInt;
+
+    fn mint_matrix(v: &[&[i64]]) -> Matrix<ModInt> {
+        Matrix::new(
+            v.iter()
+                .map(|row| row.iter().map(|x| x.mint()).collect())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_fibonacci() {
+        let m = Matrix::new(vec![vec![1_i64, 1], vec![1, 0]]);
+        let p = m.pow(10);
+        assert_eq!(p[0][1], 55);
+        assert_eq!(m.pow(0), Matrix::identity(2, 1_i64));
+
+        let m = mint_matrix(&[&[1, 1], &[1, 0]]);
+        assert_eq!(m.pow(1_000_000_000_000_000_000)[0][1], 209_783_453);
+    }
+
+    #[test]
+    fn test_mul() {
+        let a = Matrix::new(vec![vec![1_i64, 2, 3], vec![4, 5, 6]]);
+        let b = Matrix::new(vec![vec![7_i64, 8], vec![9, 10], vec![11, 12]]);
+        assert_eq!(&a * &b, Matrix::new(vec![vec![58, 64], vec![139, 154]]));
+        assert_eq!(a.transpose().rows(), 3);
+        assert_eq!(a.transpose()[2][1], 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix must have at least one row")]
+    fn test_empty_identity() {
+        Matrix::identity(0, 1_i64);
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix must have at least one column")]
+    fn test_empty_row() {
+        Matrix::<i64>::new(vec![vec![]]);
+    }
+
+    #[test]
+    fn test_min_plus() {
+        // shortest paths with exactly k edges
+        let inf = MinPlus(i64::MAX);
+        let g = Matrix::new(vec![
+            vec![inf, MinPlus(1), MinPlus(5)],
+            vec![inf, inf, MinPlus(2)],
+            vec![MinPlus(1), inf, inf],
+        ]);
+        let g2 = g.pow(2);
+        assert_eq!(g2[0][2], MinPlus(3));
+        assert_eq!(g2[0][0], MinPlus(6));
+        assert_eq!(g2[1][1], inf);
+        assert_eq!(g.pow(0)[1][1], MinPlus(0));
+    }
+
+    #[test]
+    fn test_max_plus() {
+        let ninf = MaxPlus(i64::MIN);
+        let g = Matrix::new(vec![
+            vec![ninf, MaxPlus(1), MaxPlus(5)],
+            vec![ninf, ninf, MaxPlus(2)],
+            vec![MaxPlus(1), ninf, ninf],
+        ]);
+        let g3 = g.pow(3);
+        assert_eq!(g3[0][0], MaxPlus(4));
+        assert_eq!(g3[0][1], MaxPlus(7));
+        assert_eq!(g3[1][0], ninf);
+    }
+
+    #[test]
+    fn test_determinant() {
+        let m = mint_matrix(&[&[3, 1, 4], &[1, 5, 9], &[2, 6, 5]]);
+        assert_eq!(m.determinant(), (-90_i64).mint());
+        let singular = mint_matrix(&[&[1, 2], &[2, 4]]);
+        assert_eq!(singular.determinant(), 0);
+        let swapped = mint_matrix(&[&[0, 1], &[1, 0]]);
+        assert_eq!(swapped.determinant(), (-1_i64).mint());
+    }
+
+    #[test]
+    fn test_inverse() {
+        let m = mint_matrix(&[&[3, 1, 4], &[1, 5, 9], &[2, 6, 5]]);
+        let inv = m.inverse().unwrap();
+        assert_eq!(&m * &inv, Matrix::identity(3, 1.mint()));
+        assert!(mint_matrix(&[&[1, 2], &[2, 4]]).inverse().is_none());
+    }
+
+    #[test]
+    fn test_rank() {
+        assert_eq!(mint_matrix(&[&[1, 2], &[2, 4]]).rank(), 1);
+        assert_eq!(mint_matrix(&[&[1, 2, 3], &[4, 5, 6], &[7, 8, 9]]).rank(), 2);
+        assert_eq!(mint_matrix(&[&[0, 0, 0]]).rank(), 0);
+        assert_eq!(mint_matrix(&[&[0, 1], &[1, 0], &[1, 1]]).rank(), 2);
+    }
+}
@@ -1,4 +1,5 @@
 #![allow(clippy::suspicious_arithmetic_impl, clippy::suspicious_op_assign_impl)]
+use crate::integer::{ext_gcd, factorize, gcd};
 use cargo_snippet::snippet;
 
 /// 累乗のmod
@@ -21,25 +22,120 @@ pub fn mod_pow(x: i64, n: i64, m: i64) -> i64 {
 /// mod m での a の逆元を求める
 /// m と a が互いに素でなければならないことに注意
 /// cf. [「1000000007 で割ったあまり」の求め方を総特集！ 〜 逆元から離散対数まで 〜 - Qiita](https://qiita.com/drken/items/3b4fdf0a78e7a138cd9a)
-#[snippet("MOD_INT")]
+#[snippet("MOD_INT", include = "EXT_GCD")]
 pub fn mod_inv(val: i64, modulo: i64) -> i64 {
-    use std::mem::swap;
-    let mut a = val;
-    let mut b = modulo;
-    let mut ret = 1;
-    let mut v = 0;
-    while b > 0 {
-        let t = a / b;
-        a -= t * b;
-        swap(&mut a, &mut b);
-        ret -= t * v;
-        swap(&mut ret, &mut v);
-    }
-    ret %= modulo;
-    if ret < 0 {
-        ret += modulo;
-    }
-    ret
+    let (_, x, _) = ext_gcd(val.rem_euclid(modulo), modulo);
+    x.rem_euclid(modulo)
+}
+
+/// mod p での a の平方根 (Tonelli–Shanks)
+/// p は素数。存在しなければ `None`、存在すれば小さい方の根を返す
+/// Time complexity: O(log^2 p)
+#[snippet("MOD_SQRT", include = "MOD_INT")]
+pub fn mod_sqrt(a: i64, p: i64) -> Option<i64> {
+    let a = a.rem_euclid(p);
+    if a == 0 || p == 2 {
+        return Some(a);
+    }
+    if mod_pow(a, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+    // p - 1 = q * 2^s
+    let mut q = p - 1;
+    let mut s = 0;
+    while q % 2 == 0 {
+        q /= 2;
+        s += 1;
+    }
+    let mut z = 2;
+    while mod_pow(z, (p - 1) / 2, p) != p - 1 {
+        z += 1;
+    }
+    let mut m = s;
+    let mut c = mod_pow(z, q, p);
+    let mut t = mod_pow(a, q, p);
+    let mut r = mod_pow(a, (q + 1) / 2, p);
+    while t != 1 {
+        let mut i = 0;
+        let mut tt = t;
+        while tt != 1 {
+            tt = tt * tt % p;
+            i += 1;
+        }
+        let b = mod_pow(c, 1 << (m - i - 1), p);
+        m = i;
+        c = b * b % p;
+        t = t * c % p;
+        r = r * b % p;
+    }
+    Some(std::cmp::min(r, p - r))
+}
+
+/// 離散対数 (Baby-step Giant-step)
+/// `a^x ≡ b (mod m)` を満たす最小の非負整数 `x` を求める。存在しなければ `None`
+/// a と m が互いに素でなくてもよい
+/// Time complexity: O(sqrt(m))
+/// cf. [「1000000007 で割ったあまり」の求め方を総特集！ 〜 逆元から離散対数まで 〜 - Qiita](https://qiita.com/drken/items/3b4fdf0a78e7a138cd9a)
+#[snippet("DISCRETE_LOG", include = "MOD_INT", include = "GCD")]
+pub fn discrete_log(a: i64, b: i64, m: i64) -> Option<i64> {
+    if m == 1 {
+        return Some(0);
+    }
+    let mut a = a.rem_euclid(m);
+    let mut b = b.rem_euclid(m);
+    let mut m = m;
+    // k * a^x ≡ b (mod m) の形に保ちながら、a と m が互いに素になるまで割っていく
+    let mut k = 1 % m;
+    let mut offset = 0;
+    loop {
+        if k == b {
+            return Some(offset);
+        }
+        let g = gcd(a, m);
+        if g == 1 {
+            break;
+        }
+        if b % g != 0 {
+            return None;
+        }
+        b /= g;
+        k = k * (a / g) % (m / g);
+        m /= g;
+        a %= m;
+        offset += 1;
+    }
+
+    let n = (m as f64).sqrt() as i64 + 1;
+    // baby-step: b * a^j -> j
+    let mut table = std::collections::HashMap::new();
+    let mut cur = b;
+    for j in 0..n {
+        table.insert(cur, j);
+        cur = cur * a % m;
+    }
+    // giant-step: k * a^(n i)
+    let an = mod_pow(a, n, m);
+    let mut cur = k;
+    for i in 1..=n {
+        cur = cur * an % m;
+        if let Some(&j) = table.get(&cur) {
+            return Some(n * i - j + offset);
+        }
+    }
+    None
+}
+
+/// 素数 p の最小の原始根
+/// p - 1 を素因数分解し、すべての素因数 q について g^((p - 1) / q) ≠ 1 となる g を探す
+#[snippet("PRIMITIVE_ROOT", include = "MOD_INT", include = "INT")]
+pub fn primitive_root(p: i64) -> i64 {
+    if p == 2 {
+        return 1;
+    }
+    let factors: Vec<i64> = factorize(p - 1).keys().copied().collect();
+    (2..p)
+        .find(|&g| factors.iter().all(|&q| mod_pow(g, (p - 1) / q, p) != 1))
+        .unwrap()
 }
 
 #[snippet("MOD_INT")]
@@ -66,6 +162,53 @@ impl ModInt {
             modulo,
         }
     }
+
+    pub fn value(&self) -> i64 {
+        self.value
+    }
+
+    pub fn modulo(&self) -> i64 {
+        self.modulo
+    }
+
+    pub fn pow(&self, n: u64) -> Self {
+        let mut res = Self::new(1, self.modulo);
+        let mut x = *self;
+        let mut n = n;
+        while n > 0 {
+            if n & 1 == 1 {
+                res *= x;
+            }
+            x *= x;
+            n >>= 1;
+        }
+        res
+    }
+
+    /// 逆元を求める
+    /// value と modulo が互いに素でなければならないことに注意
+    pub fn inv(&self) -> Self {
+        Self::new(mod_inv(self.value, self.modulo), self.modulo)
+    }
+}
+
+#[snippet("MOD_INT")]
+impl PartialEq for ModInt {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.modulo == other.modulo
+    }
+}
+
+#[snippet("MOD_INT")]
+impl Eq for ModInt {}
+
+#[snippet("MOD_INT")]
+impl std::ops::Neg for ModInt {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.value, self.modulo)
+    }
 }
 
 #[snippet("MOD_INT")]
@@ -263,6 +406,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_mod_sqrt() {
+        for &p in [2, 3, 13, 17, 1_000_000_007, 998_244_353].iter() {
+            for a in 0..100 {
+                match mod_sqrt(a, p) {
+                    Some(r) => assert_eq!(r * r % p, a % p),
+                    None => assert_eq!(mod_pow(a, (p - 1) / 2, p), p - 1),
+                }
+            }
+        }
+        assert_eq!(mod_sqrt(4, 13), Some(2));
+        assert_eq!(mod_sqrt(2, 13), None);
+        assert_eq!(mod_sqrt(-1, 13), Some(5));
+    }
+
+    #[test]
+    fn test_discrete_log() {
+        assert_eq!(discrete_log(2, 1, 5), Some(0));
+        assert_eq!(discrete_log(2, 3, 5), Some(3));
+        assert_eq!(discrete_log(3, 7, 11), None);
+        assert_eq!(discrete_log(4, 7, 10), None);
+        // non-coprime
+        assert_eq!(discrete_log(2, 0, 8), Some(3));
+        assert_eq!(discrete_log(6, 6, 12), Some(1));
+        assert_eq!(discrete_log(6, 0, 12), Some(2));
+        assert_eq!(discrete_log(6, 3, 12), None);
+        assert_eq!(discrete_log(0, 0, 7), Some(1));
+        assert_eq!(discrete_log(5, 3, 1), Some(0));
+        for m in 1..40 {
+            for a in 0..m {
+                for b in 0..m {
+                    let expected = (0..2 * m).find(|&x| mod_pow(a, x, m) % m == b);
+                    assert_eq!(discrete_log(a, b, m), expected, "{} {} {}", a, b, m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_primitive_root() {
+        assert_eq!(primitive_root(2), 1);
+        assert_eq!(primitive_root(7), 3);
+        assert_eq!(primitive_root(998_244_353), 3);
+        assert_eq!(primitive_root(1_000_000_007), 5);
+        assert_eq!(primitive_root(754_974_721), 11);
+    }
+
     #[test]
     fn test_mod_int() {
         let mint = 43.mint_with_mod(13);
@@ -285,4 +475,16 @@ mod tests {
         mint /= 3;
         assert_eq!(mint, 9);
     }
+
+    #[test]
+    fn test_mod_int_pow_inv() {
+        let mint = 3.mint_with_mod(13);
+        assert_eq!(mint.pow(0), 1);
+        assert_eq!(mint.pow(3), 1);
+        assert_eq!(mint.pow(5), 9);
+        assert_eq!(mint.inv(), 9);
+        assert_eq!(mint * mint.inv(), 1);
+        assert_eq!(-mint, 10);
+        assert_eq!(mint, 16.mint_with_mod(13));
+    }
 }
@@ -0,0 +1,235 @@
+use crate::integer::{factorize, Int};
+use crate::sieve::Sieve;
+use cargo_snippet::snippet;
+
+/// オイラーの φ 関数: 1 以上 n 以下で n と互いに素な整数の個数 (φ(0) = 0 とする)
+/// Time complexity: O(sqrt(n))
+#[snippet("MULTIPLICATIVE", include = "INT")]
+pub fn euler_phi<T>(n: T) -> T
+where
+    T: Int,
+{
+    if n.is_zero() {
+        return n;
+    }
+    factorize(n.clone())
+        .into_iter()
+        .fold(n, |acc, (p, _)| acc / p.clone() * (p - T::one()))
+}
+
+/// メビウス関数: n が平方因子を持てば 0、そうでなければ (-1)^(素因数の個数) (μ(0) = 0 とする)
+/// Time complexity: O(sqrt(n))
+#[snippet("MULTIPLICATIVE")]
+pub fn mobius<T>(n: T) -> i64
+where
+    T: Int,
+{
+    if n.is_zero() {
+        return 0;
+    }
+    let factors = factorize(n);
+    if factors.values().any(|&e| e >= 2) {
+        0
+    } else if factors.len().is_multiple_of(2) {
+        1
+    } else {
+        -1
+    }
+}
+
+/// 約数の個数 (n >= 1)
+/// Time complexity: O(sqrt(n))
+#[snippet("MULTIPLICATIVE")]
+pub fn divisor_count<T>(n: T) -> usize
+where
+    T: Int,
+{
+    assert!(n >= T::one());
+    factorize(n).values().map(|&e| e + 1).product()
+}
+
+/// 約数の総和 (n >= 1)
+/// Time complexity: O(sqrt(n))
+#[snippet("MULTIPLICATIVE")]
+pub fn divisor_sum<T>(n: T) -> T
+where
+    T: Int,
+{
+    assert!(n >= T::one());
+    factorize(n).into_iter().fold(T::one(), |acc, (p, e)| {
+        // 1 + p + p^2 + ... + p^e
+        let mut sum = T::one();
+        let mut pk = T::one();
+        for _ in 0..e {
+            pk = pk * p.clone();
+            sum = sum + pk.clone();
+        }
+        acc * sum
+    })
+}
+
+/// 約数についてのゼータ変換: `a[n] <- Σ_{d | n} a[d]`
+/// 添字 0 は使わない
+/// Time complexity: O(n log log n)
+#[snippet("MULTIPLICATIVE", include = "SIEVE")]
+pub fn divisor_zeta_transform<T>(a: &mut [T])
+where
+    T: Copy + std::ops::Add<Output = T>,
+{
+    let n = a.len().saturating_sub(1);
+    for &p in Sieve::new(n).primes() {
+        for i in 1..=(n / p) {
+            a[i * p] = a[i * p] + a[i];
+        }
+    }
+}
+
+/// 約数についてのメビウス変換 (`divisor_zeta_transform` の逆変換)
+/// Time complexity: O(n log log n)
+#[snippet("MULTIPLICATIVE")]
+pub fn divisor_mobius_transform<T>(a: &mut [T])
+where
+    T: Copy + std::ops::Sub<Output = T>,
+{
+    let n = a.len().saturating_sub(1);
+    for &p in Sieve::new(n).primes() {
+        for i in (1..=(n / p)).rev() {
+            a[i * p] = a[i * p] - a[i];
+        }
+    }
+}
+
+/// 倍数についてのゼータ変換: `a[n] <- Σ_{n | m} a[m]`
+/// 添字 0 は使わない
+/// Time complexity: O(n log log n)
+#[snippet("MULTIPLICATIVE")]
+pub fn multiple_zeta_transform<T>(a: &mut [T])
+where
+    T: Copy + std::ops::Add<Output = T>,
+{
+    let n = a.len().saturating_sub(1);
+    for &p in Sieve::new(n).primes() {
+        for i in (1..=(n / p)).rev() {
+            a[i] = a[i] + a[i * p];
+        }
+    }
+}
+
+/// 倍数についてのメビウス変換 (`multiple_zeta_transform` の逆変換)
+/// Time complexity: O(n log log n)
+#[snippet("MULTIPLICATIVE")]
+pub fn multiple_mobius_transform<T>(a: &mut [T])
+where
+    T: Copy + std::ops::Sub<Output = T>,
+{
+    let n = a.len().saturating_sub(1);
+    for &p in Sieve::new(n).primes() {
+        for i in 1..=(n / p) {
+            a[i] = a[i] - a[i * p];
+        }
+    }
+}
+
+/// gcd 畳み込み: `c[k] = Σ_{gcd(i, j) = k} a[i] b[j]` (1 <= i, j <= n)
+/// `a` と `b` は同じ長さ n + 1 で、添字 0 は使わない (戻り値の 0 番目の値は意味を持たない)
+/// Time complexity: O(n log log n)
+#[snippet("MULTIPLICATIVE")]
+pub fn gcd_convolution<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T>,
+{
+    assert_eq!(a.len(), b.len());
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    multiple_zeta_transform(&mut a);
+    multiple_zeta_transform(&mut b);
+    let mut c: Vec<T> = a.iter().zip(b.iter()).map(|(&x, &y)| x * y).collect();
+    multiple_mobius_transform(&mut c);
+    c
+}
+
+/// lcm 畳み込み: `c[k] = Σ_{lcm(i, j) = k} a[i] b[j]` (1 <= i, j, k <= n)
+/// `a` と `b` は同じ長さ n + 1 で、添字 0 は使わない (戻り値の 0 番目の値は意味を持たない)
+/// Time complexity: O(n log log n)
+#[snippet("MULTIPLICATIVE")]
+pub fn lcm_convolution<T>(a: &[T], b: &[T]) -> Vec<T>
+where
+    T: Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T> + std::ops::Mul<Output = T>,
+{
+    assert_eq!(a.len(), b.len());
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    divisor_zeta_transform(&mut a);
+    divisor_zeta_transform(&mut b);
+    let mut c: Vec<T> = a.iter().zip(b.iter()).map(|(&x, &y)| x * y).collect();
+    divisor_mobius_transform(&mut c);
+    c
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer::{divosors, gcd, lcm};
+
+    #[test]
+    fn test_multiplicative_functions() {
+        let sieve = Sieve::new(1000);
+        let phi = sieve.euler_phi_table();
+        let mu = sieve.mobius_table();
+        for n in 1..=1000_usize {
+            assert_eq!(euler_phi(n), phi[n]);
+            assert_eq!(mobius(n), mu[n]);
+            assert_eq!(divisor_count(n), divosors(n).len());
+            assert_eq!(divisor_sum(n), divosors(n).iter().sum::<usize>());
+        }
+        assert_eq!(euler_phi(0_i64), 0);
+        assert_eq!(mobius(0_i64), 0);
+        assert_eq!(euler_phi(1_000_000_007_i64), 1_000_000_006);
+        assert_eq!(divisor_count(720_720_u64), 240);
+        assert_eq!(divisor_sum(1_000_000_000_000_i64), 2_499_694_822_171);
+    }
+
+    #[test]
+    fn test_divisor_transforms() {
+        let n = 100;
+        let a: Vec<i64> = (0..=n as i64).map(|i| i * i % 17 - 8).collect();
+
+        let mut zeta = a.clone();
+        divisor_zeta_transform(&mut zeta);
+        for (i, &z) in zeta.iter().enumerate().skip(1) {
+            let expected: i64 = divosors(i).iter().map(|&d| a[d]).sum();
+            assert_eq!(z, expected);
+        }
+        divisor_mobius_transform(&mut zeta);
+        assert_eq!(zeta[1..], a[1..]);
+
+        let mut zeta = a.clone();
+        multiple_zeta_transform(&mut zeta);
+        for (i, &z) in zeta.iter().enumerate().skip(1) {
+            let expected: i64 = (i..=n).step_by(i).map(|m| a[m]).sum();
+            assert_eq!(z, expected);
+        }
+        multiple_mobius_transform(&mut zeta);
+        assert_eq!(zeta[1..], a[1..]);
+    }
+
+    #[test]
+    fn test_gcd_lcm_convolution() {
+        let n = 60;
+        let a: Vec<i64> = (0..=n as i64).map(|i| i % 7 - 3).collect();
+        let b: Vec<i64> = (0..=n as i64).map(|i| i * 3 % 11 - 5).collect();
+        let mut expected_gcd = vec![0; n + 1];
+        let mut expected_lcm = vec![0; n + 1];
+        for i in 1..=n {
+            for j in 1..=n {
+                expected_gcd[gcd(i, j)] += a[i] * b[j];
+                let l = lcm(i, j);
+                if l <= n {
+                    expected_lcm[l] += a[i] * b[j];
+                }
+            }
+        }
+        assert_eq!(gcd_convolution(&a, &b)[1..], expected_gcd[1..]);
+        assert_eq!(lcm_convolution(&a, &b)[1..], expected_lcm[1..]);
+    }
+}
@@ -0,0 +1,247 @@
+use crate::combination::Comb;
+use crate::integer::Int;
+use crate::interpolation::lagrange_interpolation_consecutive;
+use crate::modulo::ModInt;
+use crate::sieve::Sieve;
+use cargo_snippet::snippet;
+
+/// floor(n / i) (1 <= i <= n) が等しくなる i の区間を列挙するイテレータ
+/// `(l, r, q)` は l <= i <= r のとき floor(n / i) = q であることを表す
+/// 区間の個数は O(sqrt(n))
+#[snippet("QUOTIENT_RANGE", include = "INT_TRAIT")]
+#[derive(Clone, Debug)]
+pub struct QuotientRange<T: Int> {
+    n: T,
+    l: T,
+}
+
+#[snippet("QUOTIENT_RANGE")]
+impl<T: Int> QuotientRange<T> {
+    pub fn new(n: T) -> Self {
+        assert!(n >= T::zero());
+        Self { n, l: T::one() }
+    }
+}
+
+#[snippet("QUOTIENT_RANGE")]
+impl<T: Int> Iterator for QuotientRange<T> {
+    type Item = (T, T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.l > self.n {
+            return None;
+        }
+        let l = self.l.clone();
+        let q = self.n.clone() / l.clone();
+        let r = self.n.clone() / q.clone();
+        self.l = r.clone().next();
+        Some((l, r, q))
+    }
+}
+
+/// n 以下の素数の個数 π(n) (Lucy_Hedgehog のアルゴリズム)
+/// Time complexity: O(n^(3/4))
+#[snippet("PRIME_COUNT", include = "INT_TRAIT")]
+pub fn prime_count(n: u64) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+    let sq = n.sqrt_floor() as usize;
+    // small[v] = S(v), large[i] = S(n / i)
+    // S(v): 2 以上 v 以下で、これまでに見た素数の倍数として篩われていないものの個数
+    let mut small: Vec<u64> = (0..=sq as u64).map(|v| v.saturating_sub(1)).collect();
+    let mut large: Vec<u64> = std::iter::once(0)
+        .chain((1..=sq as u64).map(|i| n / i - 1))
+        .collect();
+    for p in 2..=sq {
+        if small[p] == small[p - 1] {
+            continue;
+        }
+        let count = small[p - 1];
+        let p2 = (p * p) as u64;
+        let limit = sq.min((n / p2) as usize);
+        for i in 1..=limit {
+            let d = i * p;
+            let s = if d <= sq {
+                large[d]
+            } else {
+                small[(n / d as u64) as usize]
+            };
+            large[i] -= s - count;
+        }
+        for v in (p * p..=sq).rev() {
+            small[v] -= small[v / p] - count;
+        }
+    }
+    large[1]
+}
+
+/// 乗法的関数 f の和 f(1) + f(2) + ... + f(n) (Min_25 篩)
+/// 素数 p に対して f(p) = Σ_k poly[k] p^k となる多項式 `poly` と、
+/// 素数冪での値 f(p^e) を返す `f(p, e)` を与える
+/// Time complexity: O(n^(3/4) / log n)
+#[snippet(
+    "MIN25_SIEVE",
+    include = "SIEVE",
+    include = "LAGRANGE",
+    include = "QUOTIENT_RANGE"
+)]
+pub fn min25_sieve<F>(n: u64, poly: &[i64], f: F, modulo: i64) -> ModInt
+where
+    F: Fn(u64, u32) -> ModInt,
+{
+    let zero = ModInt::new(0, modulo);
+    let one = ModInt::new(1, modulo);
+    if n == 0 {
+        return zero;
+    }
+    let sq = n.sqrt_floor();
+    // n / i として現れる値を降順に並べたもの
+    let values: Vec<u64> = QuotientRange::new(n).map(|(_, _, q)| q).collect();
+    let index = |w: u64| -> usize {
+        if w <= sq {
+            values.len() - w as usize
+        } else {
+            (n / w) as usize - 1
+        }
+    };
+    let primes: Vec<u64> = Sieve::new(sq as usize)
+        .primes()
+        .iter()
+        .map(|&p| p as u64)
+        .collect();
+
+    // g[idx]: 2 以上 w 以下の素数 p についての f(p) の和
+    let mut g = vec![zero; values.len()];
+    for (k, &c) in poly.iter().enumerate() {
+        if c == 0 {
+            continue;
+        }
+        // 1^k + ... + w^k は w の k + 1 次多項式なので、k + 2 点の値を一度だけ求めて各 w で補間する
+        let comb = Comb::new(k + 2, modulo);
+        let mut ys = vec![zero];
+        for i in 1..(k + 2) {
+            let y = ys[i - 1] + ModInt::new(i as i64, modulo).pow(k as u64);
+            ys.push(y);
+        }
+        // h[idx]: 2 以上 w 以下の「篩われていない」数 i についての i^k の和
+        let mut h: Vec<ModInt> = values
+            .iter()
+            .map(|&w| lagrange_interpolation_consecutive(&ys, w, &comb) - 1)
+            .collect();
+        for &p in primes.iter() {
+            let pk = ModInt::new((p % modulo as u64) as i64, modulo).pow(k as u64);
+            let base = h[index(p - 1)];
+            for i in 0..values.len() {
+                let w = values[i];
+                if w < p * p {
+                    break;
+                }
+                h[i] = h[i] - pk * (h[index(w / p)] - base);
+            }
+        }
+        for (gi, hi) in g.iter_mut().zip(h.iter()) {
+            *gi += *hi * c;
+        }
+    }
+
+    // S(w, j): 2 以上 w 以下で最小素因数が primes[j] 以上の i についての f(i) の和
+    fn rec<F>(
+        w: u64,
+        j: usize,
+        primes: &[u64],
+        g: &[ModInt],
+        index: &dyn Fn(u64) -> usize,
+        f: &F,
+    ) -> ModInt
+    where
+        F: Fn(u64, u32) -> ModInt,
+    {
+        // primes[j] 未満の素数の分を引く (j = 0 のときは 1 以下の素数の和 = 0)
+        let below = g[index(if j == 0 { 1 } else { primes[j - 1] })];
+        let mut ret = g[index(w)] - below;
+        for (k, &p) in primes.iter().enumerate().skip(j) {
+            if p * p > w {
+                break;
+            }
+            let mut pe = p;
+            let mut e = 1;
+            while pe * p <= w {
+                ret += f(p, e) * rec(w / pe, k + 1, primes, g, index, f) + f(p, e + 1);
+                pe *= p;
+                e += 1;
+            }
+        }
+        ret
+    }
+
+    if n == 1 {
+        return one;
+    }
+    rec(n, 0, &primes, &g, &index, &f) + one
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quotient_range() {
+        for n in 0..200_i64 {
+            let mut expected = Vec::new();
+            for i in 1..=n {
+                match expected.last_mut() {
+                    Some((_, r, q)) if *q == n / i => *r = i,
+                    _ => expected.push((i, i, n / i)),
+                }
+            }
+            assert_eq!(QuotientRange::new(n).collect::<Vec<_>>(), expected);
+        }
+        let n = 1_000_000_000_000_u64;
+        assert_eq!(QuotientRange::new(n).count(), 1_999_999);
+    }
+
+    #[test]
+    fn test_prime_count() {
+        let sieve = Sieve::new(10000);
+        let mut count = 0;
+        for n in 0..=10000_u64 {
+            if sieve.is_prime(n as usize) {
+                count += 1;
+            }
+            assert_eq!(prime_count(n), count);
+        }
+        assert_eq!(prime_count(1_000_000_000), 50_847_534);
+        assert_eq!(prime_count(10_000_000_000), 455_052_511);
+    }
+
+    #[test]
+    fn test_min25_sieve() {
+        let modulo = 1_000_000_007;
+        let n = 500;
+        let sieve = Sieve::new(n);
+        let phi = sieve.euler_phi_table();
+        // φ(p) = p - 1, φ(p^e) = p^(e - 1) (p - 1)
+        let phi_pe = |p: u64, e: u32| ModInt::new((p.pow(e - 1) * (p - 1)) as i64, modulo);
+        // σ(p) = p + 1, σ(p^e) = 1 + p + ... + p^e
+        let sigma_pe = |p: u64, e: u32| ModInt::new(((p.pow(e + 1) - 1) / (p - 1)) as i64, modulo);
+        // f = 1 なら和は n
+        let one_pe = |_: u64, _: u32| ModInt::new(1, modulo);
+        let mut phi_sum = 0;
+        let mut sigma_sum = 0;
+        for (m, &phi_m) in phi.iter().enumerate().skip(1) {
+            phi_sum += phi_m as i64;
+            sigma_sum += crate::integer::divosors(m).iter().sum::<usize>() as i64;
+            assert_eq!(min25_sieve(m as u64, &[-1, 1], phi_pe, modulo), phi_sum);
+            assert_eq!(min25_sieve(m as u64, &[1, 1], sigma_pe, modulo), sigma_sum);
+            assert_eq!(min25_sieve(m as u64, &[1], one_pe, modulo), m as i64);
+        }
+        // Σ_{i <= n} σ(i) = Σ_{d <= n} d floor(n / d)
+        let n = 1_000_000_000_u64;
+        let expected = QuotientRange::new(n).fold(ModInt::new(0, modulo), |acc, (l, r, q)| {
+            let sum = (l + r) as u128 * (r - l + 1) as u128 / 2 % modulo as u128;
+            acc + ModInt::new(sum as i64, modulo) * (q % modulo as u64)
+        });
+        assert_eq!(min25_sieve(n, &[1, 1], sigma_pe, modulo), expected);
+    }
+}
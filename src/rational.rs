@@ -1,6 +1,7 @@
 // Mostly referred to: https://github.com/rust-num/num-rational
 
 use crate::integer::{gcd, Int};
+use crate::modulo::ModInt;
 use cargo_snippet::snippet;
 
 #[snippet("RATIONAL", include = "GCD", include = "INT_TRAIT")]
@@ -12,13 +13,19 @@ pub struct Rational<T: Int> {
     denomitor: T,
 }
 
+/// `a/b` の形で出力する (分母が 1 なら `a` のみ)
+/// `{:.3}` のように精度を指定すると、`to_decimal_string` と同じ小数表記になる
 #[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
 impl<T> std::fmt::Display for Rational<T>
 where
     T: Int + std::fmt::Display,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "({} / {})", self.numerator, self.denomitor)
+        match f.precision() {
+            Some(precision) => write!(f, "{}", self.to_decimal_string(precision)),
+            None if self.denomitor.is_one() => write!(f, "{}", self.numerator),
+            None => write!(f, "{}/{}", self.numerator, self.denomitor),
+        }
     }
 }
 
@@ -32,6 +39,45 @@ where
     }
 }
 
+/// 文字列から有理数への変換に失敗したときのエラー
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseRationalError;
+
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl<T> std::str::FromStr for Rational<T>
+where
+    T: Int + std::str::FromStr,
+{
+    type Err = ParseRationalError;
+
+    /// `"a/b"`, `"a"`, `"-3.125"` のような有限小数を受け付ける
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |t: &str| t.parse::<T>().map_err(|_| ParseRationalError);
+        if let Some((numer, denom)) = s.split_once('/') {
+            let (numer, denom) = (parse(numer)?, parse(denom)?);
+            if denom.is_zero() {
+                return Err(ParseRationalError);
+            }
+            return Ok(Rational::new(numer, denom));
+        }
+        match s.split_once('.') {
+            Some((int, frac)) => {
+                let unsigned = int.strip_prefix(&['+', '-'][..]).unwrap_or(int);
+                let is_digits = |t: &str| !t.is_empty() && t.bytes().all(|c| c.is_ascii_digit());
+                if !is_digits(unsigned) || !is_digits(frac) {
+                    return Err(ParseRationalError);
+                }
+                // 3.125 = 3125 / 1000
+                let numer = parse(&format!("{}{}", int, frac))?;
+                let denom = parse(&format!("1{}", "0".repeat(frac.len())))?;
+                Ok(Rational::new(numer, denom))
+            }
+            None => Ok(Rational::from_integer(parse(s)?)),
+        }
+    }
+}
+
 #[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
 impl<T: Int> Rational<T> {
     pub fn new(numerator: T, denomitor: T) -> Rational<T> {
@@ -47,31 +93,231 @@ impl<T: Int> Rational<T> {
         Rational::new(t, T::one())
     }
 
+    /// Gets an immutable reference to the numerator.
+    pub fn numer(&self) -> &T {
+        &self.numerator
+    }
+
+    /// Gets an immutable reference to the denominator (always positive).
+    pub fn denom(&self) -> &T {
+        &self.denomitor
+    }
+
     pub fn to_integer(&self) -> T {
-        self.truncate().numerator
+        self.numerator.clone() / self.denomitor.clone()
     }
 
     /// Round value towards zero.
     pub fn truncate(&self) -> Rational<T> {
-        Rational::from_integer(self.numerator / self.denomitor)
+        Rational::from_integer(self.to_integer())
     }
 
     /// Round value towards minus infinity.
     pub fn floor(&self) -> Rational<T> {
+        let (n, d) = (self.numerator.clone(), self.denomitor.clone());
         if *self < T::zero() {
-            Rational::from_integer((self.numerator - self.denomitor + T::one()) / self.denomitor)
+            Rational::from_integer((n - d.clone() + T::one()) / d)
         } else {
-            Rational::from_integer(self.numerator / self.denomitor)
+            Rational::from_integer(n / d)
         }
     }
 
     /// Round value towards plus infinity.
     pub fn ceil(&self) -> Rational<T> {
+        let (n, d) = (self.numerator.clone(), self.denomitor.clone());
         if *self < T::zero() {
-            Rational::from_integer(self.numerator / self.denomitor)
+            Rational::from_integer(n / d)
+        } else {
+            Rational::from_integer((n + d.clone() - T::one()) / d)
+        }
+    }
+
+    pub fn abs(&self) -> Rational<T> {
+        if self.numerator < T::zero() {
+            -self.clone()
+        } else {
+            self.clone()
+        }
+    }
+
+    /// 逆数 (0 のときは panic)
+    pub fn recip(&self) -> Rational<T> {
+        assert!(!self.numerator.is_zero(), "reciprocal of zero");
+        if self.numerator < T::zero() {
+            Rational {
+                numerator: T::zero() - self.denomitor.clone(),
+                denomitor: T::zero() - self.numerator.clone(),
+            }
+        } else {
+            Rational {
+                numerator: self.denomitor.clone(),
+                denomitor: self.numerator.clone(),
+            }
+        }
+    }
+
+    /// `exp` が負なら逆数の累乗
+    pub fn pow(&self, exp: i32) -> Rational<T> {
+        let base = if exp < 0 { self.recip() } else { self.clone() };
+        let exp = exp.unsigned_abs();
+        // 既約分数の累乗は既約なので reduce は不要
+        Rational {
+            numerator: base.numerator.pow(exp),
+            denomitor: base.denomitor.pow(exp),
+        }
+    }
+
+    /// 小数点以下 `precision` 桁の 10 進表記
+    /// 正確な値を四捨五入する (ちょうど半分のときは 0 から遠い方へ丸める)
+    pub fn to_decimal_string(&self, precision: usize) -> String
+    where
+        T: std::fmt::Display,
+    {
+        let d = self.denomitor.clone();
+        // 分子の abs は i64::MIN などで溢れるので、商は文字列から符号を外し、余りは |rem| < d の abs をとる
+        let (q, rem) = self.numerator.div_rem(&d);
+        let mut digits = q.to_string().trim_start_matches('-').as_bytes().to_vec();
+        let mut int_len = digits.len();
+        let mut rem = rem.abs();
+        for _ in 0..precision {
+            // floor(10 rem / d) を、10 rem を作らずに rem を 10 回足して求める
+            let mut digit = b'0';
+            let mut acc = T::zero();
+            for _ in 0..10 {
+                if acc >= d.clone() - rem.clone() {
+                    acc = acc - (d.clone() - rem.clone());
+                    digit += 1;
+                } else {
+                    acc = acc + rem.clone();
+                }
+            }
+            digits.push(digit);
+            rem = acc;
+        }
+        // 残りが d / 2 以上なら繰り上げる
+        if rem >= d - rem.clone() {
+            let mut i = digits.len();
+            loop {
+                if i == 0 {
+                    digits.insert(0, b'1');
+                    int_len += 1;
+                    break;
+                }
+                i -= 1;
+                if digits[i] == b'9' {
+                    digits[i] = b'0';
+                } else {
+                    digits[i] += 1;
+                    break;
+                }
+            }
+        }
+        let mut ret = String::new();
+        // -0.00 とは書かない
+        if self.numerator < T::zero() && digits.iter().any(|&c| c != b'0') {
+            ret.push('-');
+        }
+        ret.push_str(std::str::from_utf8(&digits[..int_len]).unwrap());
+        if precision > 0 {
+            ret.push('.');
+            ret.push_str(std::str::from_utf8(&digits[int_len..]).unwrap());
+        }
+        ret
+    }
+
+    /// 分子と分母をそれぞれ (上位 64 bit) * 2^e の形にしてから割るので、誤差を含みうる
+    /// 分子・分母が f64 の範囲を超えていても、値が範囲内なら有限の値を返す
+    pub fn to_f64(&self) -> f64 {
+        let split = |x: &T| -> (f64, i32) {
+            let shift = bit_length(x).saturating_sub(64);
+            let top = if shift == 0 {
+                x.clone()
+            } else {
+                x.clone() / Int::pow(T::one().next(), shift)
+            };
+            (low_bits_u64(&top) as f64, shift as i32)
+        };
+        let (n, en) = split(&self.numerator);
+        let (d, ed) = split(&self.denomitor);
+        let e = en - ed;
+        // 2^e だけで溢れないよう 2 回に分けて掛ける
+        let ret = n / d * 2f64.powi(e / 2) * 2f64.powi(e - e / 2);
+        if self.numerator < T::zero() {
+            -ret
+        } else {
+            ret
+        }
+    }
+
+    /// 途中計算がオーバーフローするときは `None` を返す加算
+    /// 分母の gcd で約分してから掛けるので、分母の積は作らない
+    /// 約分後の値が `T` に収まっても分子の途中計算で溢れることはある (`wide_add` を参照)
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add_sub(rhs, false)
+    }
+
+    /// 途中計算がオーバーフローするときは `None` を返す減算
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        self.checked_add_sub(rhs, true)
+    }
+
+    /// 途中計算がオーバーフローするときは `None` を返す乗算
+    /// 分子と分母を交差して約分してから掛けるので、`None` になるのは結果が `T` に収まらないときだけ
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        let gcd1 = gcd(self.numerator.clone(), rhs.denomitor.clone()).abs();
+        let gcd2 = gcd(self.denomitor.clone(), rhs.numerator.clone()).abs();
+        let numer = (self.numerator.clone() / gcd1.clone())
+            .checked_mul(rhs.numerator.clone() / gcd2.clone())?;
+        let denom = (self.denomitor.clone() / gcd2).checked_mul(rhs.denomitor.clone() / gcd1)?;
+        // 既に既約なので reduce は不要
+        Some(Rational {
+            numerator: numer,
+            denomitor: denom,
+        })
+    }
+
+    /// 途中計算がオーバーフローするとき、または 0 で割るときは `None` を返す除算
+    pub fn checked_div(&self, rhs: &Self) -> Option<Self> {
+        if rhs.numerator.is_zero() {
+            return None;
+        }
+        let rhs_inv = if rhs.numerator < T::zero() {
+            Rational {
+                numerator: T::zero().checked_sub(rhs.denomitor.clone())?,
+                denomitor: T::zero().checked_sub(rhs.numerator.clone())?,
+            }
         } else {
-            Rational::from_integer((self.numerator + self.denomitor - T::one()) / self.denomitor)
+            Rational {
+                numerator: rhs.denomitor.clone(),
+                denomitor: rhs.numerator.clone(),
+            }
+        };
+        self.checked_mul(&rhs_inv)
+    }
+
+    /// n1 / d1 ± n2 / d2 を g = gcd(d1, d2) を使って
+    /// t = n1 (d2 / g) ± n2 (d1 / g), g2 = gcd(t, g) として (t / g2) / ((d1 / g) (d2 / g2)) で求める
+    fn checked_add_sub(&self, rhs: &Self, sub: bool) -> Option<Self> {
+        let g = gcd(self.denomitor.clone(), rhs.denomitor.clone());
+        let self_mul = rhs.denomitor.clone() / g.clone();
+        let rhs_mul = self.denomitor.clone() / g.clone();
+        let lhs = self.numerator.clone().checked_mul(self_mul)?;
+        let rhs_numer = rhs.numerator.clone().checked_mul(rhs_mul.clone())?;
+        let t = if sub {
+            lhs.checked_sub(rhs_numer)?
+        } else {
+            lhs.checked_add(rhs_numer)?
+        };
+        if t.is_zero() {
+            return Some(Rational::from_integer(T::zero()));
         }
+        let g2 = gcd(t.clone(), g).abs();
+        let denom = rhs_mul.checked_mul(rhs.denomitor.clone() / g2.clone())?;
+        // 既に既約なので reduce は不要
+        Some(Rational {
+            numerator: t / g2,
+            denomitor: denom,
+        })
     }
 
     /// Puts self into lowest terms, with denomitor > 0.
@@ -88,18 +334,223 @@ impl<T: Int> Rational<T> {
             return;
         }
 
-        let g = gcd(self.numerator, self.denomitor);
-        self.numerator = self.numerator / g;
-        self.denomitor = self.denomitor / g;
+        // 符号付きの gcd は負になりうるので、MIN / -1 を避けるため絶対値をとる
+        let g = gcd(self.numerator.clone(), self.denomitor.clone()).abs();
+        self.numerator = self.numerator.clone() / g.clone();
+        self.denomitor = self.denomitor.clone() / g;
 
         // keep denomitor positive
         if self.denomitor < T::zero() {
-            self.numerator = T::zero() - self.numerator;
-            self.denomitor = T::zero() - self.denomitor;
+            self.numerator = T::zero() - self.numerator.clone();
+            self.denomitor = T::zero() - self.denomitor.clone();
+        }
+    }
+}
+
+/// 2 倍の幅を持つ整数型があるプリミティブ整数型
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+pub trait WideInt: Int {
+    type Wide: Int;
+    fn widen(self) -> Self::Wide;
+    /// `Self` に収まらないときは `None`
+    fn narrow(wide: Self::Wide) -> Option<Self>;
+}
+
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+macro_rules! impl_wide_int_for_numerics {
+    ( $( $t: ty => $w: ty ),* ) => {
+        $(
+            impl WideInt for $t {
+                type Wide = $w;
+                fn widen(self) -> $w {
+                    self as $w
+                }
+                fn narrow(wide: $w) -> Option<Self> {
+                    use std::convert::TryFrom;
+                    <$t>::try_from(wide).ok()
+                }
+            }
+        )*
+    };
+}
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl_wide_int_for_numerics!(
+    u8 => u16, i8 => i16, u16 => u32, i16 => i32, u32 => u64, i32 => i64,
+    u64 => u128, i64 => i128, usize => u128, isize => i128
+);
+
+/// `Rational<i64>` なら i128 のように、2 倍の幅の整数型で計算してから約分する演算
+/// 約分後の値が `T` に収まれば途中計算で溢れることはない
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl<T: WideInt> Rational<T> {
+    pub fn wide_add(&self, rhs: &Self) -> Option<Self> {
+        Self::narrow(self.widen().checked_add(&rhs.widen())?)
+    }
+
+    pub fn wide_sub(&self, rhs: &Self) -> Option<Self> {
+        Self::narrow(self.widen().checked_sub(&rhs.widen())?)
+    }
+
+    pub fn wide_mul(&self, rhs: &Self) -> Option<Self> {
+        Self::narrow(self.widen().checked_mul(&rhs.widen())?)
+    }
+
+    /// 0 で割るときも `None`
+    pub fn wide_div(&self, rhs: &Self) -> Option<Self> {
+        Self::narrow(self.widen().checked_div(&rhs.widen())?)
+    }
+
+    fn widen(&self) -> Rational<T::Wide> {
+        Rational {
+            numerator: self.numerator.clone().widen(),
+            denomitor: self.denomitor.clone().widen(),
+        }
+    }
+
+    fn narrow(wide: Rational<T::Wide>) -> Option<Self> {
+        Some(Rational {
+            numerator: T::narrow(wide.numerator)?,
+            denomitor: T::narrow(wide.denomitor)?,
+        })
+    }
+}
+
+/// |x| の 2 進での桁数 (x = 0 なら 0)
+#[snippet("RATIONAL")]
+fn bit_length<T: Int>(x: &T) -> u32 {
+    let two = T::one().next();
+    let mut x = x.clone();
+    let mut ret = 0;
+    while !x.is_zero() {
+        x = x / two.clone();
+        ret += 1;
+    }
+    ret
+}
+
+/// |x| の下位 64 bit
+#[snippet("RATIONAL")]
+fn low_bits_u64<T: Int>(x: &T) -> u64 {
+    let two = T::one().next();
+    let mut x = x.clone();
+    let mut ret = 0;
+    for i in 0..64 {
+        if x.is_zero() {
+            break;
+        }
+        let (q, r) = x.div_rem(&two);
+        if !r.is_zero() {
+            ret |= 1 << i;
+        }
+        x = q;
+    }
+    ret
+}
+
+/// x を T に変換する。T に収まらなければ `None`
+#[snippet("RATIONAL")]
+fn int_from_u64<T: Int>(x: u64) -> Option<T> {
+    let mut ret = T::zero();
+    for i in (0..64).rev() {
+        ret = ret.clone().checked_add(ret)?;
+        if x >> i & 1 == 1 {
+            ret = ret.checked_add(T::one())?;
+        }
+    }
+    Some(ret)
+}
+
+/// a / b を a * b^(-1) mod `modulo` に変換する (b は `modulo` と互いに素であること)
+#[snippet("RATIONAL_MOD_INT", include = "RATIONAL", include = "MOD_INT")]
+impl<T: Int> Rational<T> {
+    pub fn to_mint(&self, modulo: i64) -> ModInt {
+        // modulo が T で表せるなら mod_floor で [0, modulo) に落とす。表せないなら |x| < modulo
+        let m = int_from_u64::<T>(modulo as u64);
+        let residue = |x: &T| {
+            let r = match &m {
+                Some(m) => x.mod_floor(m),
+                None => x.clone(),
+            };
+            let v = ModInt::new((low_bits_u64(&r) % modulo as u64) as i64, modulo);
+            if r < T::zero() {
+                -v
+            } else {
+                v
+            }
+        };
+        let denom = residue(&self.denomitor);
+        assert!(denom != 0, "denominator is divisible by modulo");
+        residue(&self.numerator) / denom
+    }
+}
+
+/// 常に既約で分母が正なので、分子と分母のハッシュは `Eq` と整合する
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl<T> std::hash::Hash for Rational<T>
+where
+    T: Int,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.numerator.hash(state);
+        self.denomitor.hash(state);
+    }
+}
+
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl<T> std::ops::Neg for Rational<T>
+where
+    T: Int,
+{
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Rational {
+            numerator: T::zero() - self.numerator,
+            denomitor: self.denomitor,
         }
     }
 }
 
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl<T> std::iter::Sum for Rational<T>
+where
+    T: Int,
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Rational::from_integer(T::zero()), |acc, x| acc + x)
+    }
+}
+
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl<'a, T> std::iter::Sum<&'a Rational<T>> for Rational<T>
+where
+    T: Int,
+{
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Rational::from_integer(T::zero()), |acc, x| acc + x.clone())
+    }
+}
+
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl<T> std::iter::Product for Rational<T>
+where
+    T: Int,
+{
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Rational::from_integer(T::one()), |acc, x| acc * x)
+    }
+}
+
+#[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
+impl<'a, T> std::iter::Product<&'a Rational<T>> for Rational<T>
+where
+    T: Int,
+{
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(Rational::from_integer(T::one()), |acc, x| acc * x.clone())
+    }
+}
+
 #[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
 impl<T> From<T> for Rational<T>
 where
@@ -139,7 +590,7 @@ where
     T: Int,
 {
     fn eq(&self, other: &T) -> bool {
-        let other = Self::from_integer(*other);
+        let other = Self::from_integer(other.clone());
         *self == other
     }
 }
@@ -158,7 +609,7 @@ macro_rules! impl_partial_eq_with_rational_for_numerics {
     }
 }
 #[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
-impl_partial_eq_with_rational_for_numerics!(u8 i8 u16 i16 u32 i32 u64 i64 usize isize);
+impl_partial_eq_with_rational_for_numerics!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize);
 
 #[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
 impl<T> Ord for Rational<T>
@@ -194,8 +645,8 @@ where
                 (true, false) => std::cmp::Ordering::Less,
                 (false, true) => std::cmp::Ordering::Greater,
                 (false, false) => {
-                    let self_new = Rational::new(self.denomitor, self_rem);
-                    let other_new = Rational::new(other.denomitor, other_rem);
+                    let self_new = Rational::new(self.denomitor.clone(), self_rem);
+                    let other_new = Rational::new(other.denomitor.clone(), other_rem);
                     self_new.cmp(&other_new).reverse()
                 }
             },
@@ -219,7 +670,7 @@ where
     T: Int,
 {
     fn partial_cmp(&self, other: &T) -> Option<std::cmp::Ordering> {
-        let other = Self::from_integer(*other);
+        let other = Self::from_integer(other.clone());
         Some(self.cmp(&other))
     }
 }
@@ -238,7 +689,7 @@ macro_rules! impl_partial_ord_with_rational_for_numerics {
     }
 }
 #[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
-impl_partial_ord_with_rational_for_numerics!(u8 i8 u16 i16 u32 i32 u64 i64 usize isize);
+impl_partial_ord_with_rational_for_numerics!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize);
 
 #[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
 impl<T> std::ops::Add<Self> for Rational<T>
@@ -248,10 +699,10 @@ where
     type Output = Self;
 
     fn add(self, rhs: Self) -> Self::Output {
-        let denom_gcd = gcd(self.denomitor, rhs.denomitor);
-        let denom_lcm = (self.denomitor.mul(rhs.denomitor)).div(denom_gcd);
-        let self_mul = (rhs.denomitor).div(denom_gcd);
-        let rhs_mul = (self.denomitor).div(denom_gcd);
+        let denom_gcd = gcd(self.denomitor.clone(), rhs.denomitor.clone());
+        let self_mul = rhs.denomitor.clone().div(denom_gcd.clone());
+        let rhs_mul = self.denomitor.clone().div(denom_gcd);
+        let denom_lcm = self.denomitor.mul(self_mul.clone());
         let numer = self.numerator.mul(self_mul).add(rhs.numerator.mul(rhs_mul));
         Rational::new(numer, denom_lcm)
     }
@@ -276,7 +727,7 @@ where
     T: Int,
 {
     fn add_assign(&mut self, other: Self) {
-        let add = std::ops::Add::<Self>::add(*self, other);
+        let add = std::ops::Add::<Self>::add(self.clone(), other);
         self.numerator = add.numerator;
         self.denomitor = add.denomitor;
     }
@@ -288,7 +739,7 @@ where
     T: Int,
 {
     fn add_assign(&mut self, other: T) {
-        let add = std::ops::Add::<T>::add(*self, other);
+        let add = std::ops::Add::<T>::add(self.clone(), other);
         self.numerator = add.numerator;
         self.denomitor = add.denomitor;
     }
@@ -302,10 +753,10 @@ where
     type Output = Self;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let denom_gcd = gcd(self.denomitor, rhs.denomitor);
-        let denom_lcm = self.denomitor.mul(rhs.denomitor).div(denom_gcd);
-        let self_mul = rhs.denomitor.div(denom_gcd);
-        let rhs_mul = self.denomitor.div(denom_gcd);
+        let denom_gcd = gcd(self.denomitor.clone(), rhs.denomitor.clone());
+        let self_mul = rhs.denomitor.clone().div(denom_gcd.clone());
+        let rhs_mul = self.denomitor.clone().div(denom_gcd);
+        let denom_lcm = self.denomitor.mul(self_mul.clone());
         let numer = self.numerator.mul(self_mul).sub(rhs.numerator.mul(rhs_mul));
         Rational::new(numer, denom_lcm)
     }
@@ -330,7 +781,7 @@ where
     T: Int,
 {
     fn sub_assign(&mut self, other: Self) {
-        let sub = *self - other;
+        let sub = self.clone() - other;
         self.numerator = sub.numerator;
         self.denomitor = sub.denomitor;
     }
@@ -342,7 +793,7 @@ where
     T: Int,
 {
     fn sub_assign(&mut self, other: T) {
-        let sub = *self - other;
+        let sub = self.clone() - other;
         self.numerator = sub.numerator;
         self.denomitor = sub.denomitor;
     }
@@ -356,10 +807,10 @@ where
     type Output = Self;
 
     fn mul(self, rhs: Self) -> Self::Output {
-        let gcd1 = gcd(self.numerator, rhs.denomitor);
-        let gcd2 = gcd(self.denomitor, rhs.numerator);
-        let num1 = self.numerator.div(gcd1);
-        let den1 = self.denomitor.div(gcd2);
+        let gcd1 = gcd(self.numerator.clone(), rhs.denomitor.clone());
+        let gcd2 = gcd(self.denomitor.clone(), rhs.numerator.clone());
+        let num1 = self.numerator.div(gcd1.clone());
+        let den1 = self.denomitor.div(gcd2.clone());
         let num2 = rhs.numerator.div(gcd2);
         let den2 = rhs.denomitor.div(gcd1);
         Self::new(num1 * num2, den1 * den2)
@@ -385,7 +836,7 @@ where
     T: Int,
 {
     fn mul_assign(&mut self, rhs: Self) {
-        let mul = *self * rhs;
+        let mul = self.clone() * rhs;
         self.numerator = mul.numerator;
         self.denomitor = mul.denomitor;
     }
@@ -397,7 +848,7 @@ where
     T: Int,
 {
     fn mul_assign(&mut self, rhs: T) {
-        let mul = *self * rhs;
+        let mul = self.clone() * rhs;
         self.numerator = mul.numerator;
         self.denomitor = mul.denomitor;
     }
@@ -435,7 +886,7 @@ where
     T: Int,
 {
     fn div_assign(&mut self, rhs: Self) {
-        let div = *self / rhs;
+        let div = self.clone() / rhs;
         self.numerator = div.numerator;
         self.denomitor = div.denomitor;
     }
@@ -447,7 +898,7 @@ where
     T: Int,
 {
     fn div_assign(&mut self, rhs: T) {
-        let div = *self / rhs;
+        let div = self.clone() / rhs;
         self.numerator = div.numerator;
         self.denomitor = div.denomitor;
     }
@@ -485,7 +936,7 @@ macro_rules! impl_ops_for_numerics {
     }
 }
 #[snippet("RATIONAL", include = "INT_TRAIT", include = "GCD")]
-impl_ops_for_numerics!(u8 i8 u16 i16 u32 i32 u64 i64 usize isize);
+impl_ops_for_numerics!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize);
 
 #[cfg(test)]
 mod tests {
@@ -806,4 +1257,266 @@ mod tests {
         r /= Rational::new(5, 6);
         assert_eq!(r, Rational::new(-9, 10));
     }
+
+    #[test]
+    fn checked_ops() {
+        let e18 = 10_i64.pow(18);
+        let x = Rational::new(e18, 7);
+        assert_eq!(x.checked_add(&x), Some(Rational::new(2 * e18, 7)));
+        assert_eq!(x.checked_sub(&x), Some(Rational::from_integer(0)));
+        assert_eq!(
+            Rational::from_integer(i64::MAX).checked_add(&Rational::from_integer(1)),
+            None
+        );
+        assert_eq!(
+            Rational::from_integer(i64::MIN + 1).checked_sub(&Rational::new(3, 2)),
+            None
+        );
+        // 分母の積 2^80 は作らない
+        let y = Rational::new(1, 1_i64 << 40);
+        assert_eq!(y.checked_add(&y), Some(Rational::new(1, 1_i64 << 39)));
+        // 分母の最小公倍数は i64 に収まらないが、約分後の値は収まる
+        let g = 3 * (1_i64 << 40);
+        let a = Rational::new(1, 5_i64.pow(9) * g);
+        let b = Rational::new(2_406_522_667_733, 7_i64.pow(7) * g);
+        assert_eq!(
+            a.checked_add(&b),
+            Some(Rational::new(4_274_843, 4_825_447_265_625))
+        );
+        // 符号なし整数で負になるとき
+        let half = Rational::<u64>::new(1, 2);
+        let third = Rational::<u64>::new(1, 3);
+        assert_eq!(half.checked_sub(&third), Some(Rational::new(1, 6)));
+        assert_eq!(third.checked_sub(&half), None);
+
+        let z = Rational::new(e18, 3);
+        assert_eq!(
+            z.checked_mul(&Rational::new(3, e18)),
+            Some(Rational::from_integer(1))
+        );
+        assert_eq!(
+            z.checked_mul(&Rational::new(-6, e18 / 2)),
+            Some(Rational::from_integer(-4))
+        );
+        assert_eq!(z.checked_mul(&Rational::from_integer(10)), None);
+        assert_eq!(
+            x.checked_div(&Rational::new(e18, 11)),
+            Some(Rational::new(11, 7))
+        );
+        assert_eq!(
+            x.checked_div(&Rational::new(-e18, 11)),
+            Some(Rational::new(-11, 7))
+        );
+        assert_eq!(x.checked_div(&Rational::from_integer(0)), None);
+        assert_eq!(x.checked_div(&Rational::new(1, e18)), None);
+    }
+
+    #[test]
+    fn wide_ops() {
+        let e18 = 10_i64.pow(18);
+        // 3e18 / 7 + n / 11 = 5 / 77 だが、分子の途中計算は i64 に収まらない
+        let x = Rational::new(3 * e18, 7);
+        let y = Rational::new(-4_714_285_714_285_714_285, 11);
+        assert_eq!(x.checked_add(&y), None);
+        assert_eq!(x.wide_add(&y), Some(Rational::new(5, 77)));
+        let minus_y = Rational::new(4_714_285_714_285_714_285, 11);
+        assert_eq!(x.wide_sub(&minus_y), Some(Rational::new(5, 77)));
+        let big = Rational::new(5 * e18, 7);
+        assert_eq!(big.wide_add(&big), None);
+        assert_eq!(
+            Rational::from_integer(i64::MAX).wide_sub(&Rational::from_integer(i64::MAX)),
+            Some(Rational::from_integer(0))
+        );
+        assert_eq!(
+            Rational::from_integer(i64::MIN).wide_add(&Rational::from_integer(-1)),
+            None
+        );
+        assert_eq!(
+            Rational::new(i64::MAX, 2).wide_mul(&Rational::new(2, i64::MAX)),
+            Some(Rational::from_integer(1))
+        );
+        assert_eq!(
+            Rational::new(i64::MAX, 3).wide_div(&Rational::new(i64::MAX, 6)),
+            Some(Rational::from_integer(2))
+        );
+        assert_eq!(x.wide_div(&Rational::from_integer(0)), None);
+        let third = Rational::<u64>::new(1, 3);
+        assert_eq!(third.wide_sub(&Rational::new(1, 2)), None);
+        assert_eq!(
+            Rational::new(u64::MAX, 2).wide_add(&Rational::new(u64::MAX, 2)),
+            Some(Rational::from_integer(u64::MAX))
+        );
+        assert_eq!(
+            Rational::new(u64::MAX - 1, 3).wide_add(&Rational::new(u64::MAX - 2, 5)),
+            None
+        );
+
+        // 小さい型で総当たりして、正確な値が収まるときに限り `Some` になることを確かめる
+        let values: Vec<Rational<i8>> = (-128..=127_i16)
+            .step_by(7)
+            .flat_map(|p| (1..=127_i16).step_by(9).map(move |q| (p, q)))
+            .map(|(p, q)| Rational::new(p as i8, q as i8))
+            .collect();
+        let fits = |r: Rational<i16>| -> Option<Rational<i8>> { Rational::<i8>::narrow(r) };
+        for a in values.iter() {
+            for b in values.iter() {
+                let (wa, wb) = (a.widen(), b.widen());
+                assert_eq!(a.wide_add(b), fits(wa + wb));
+                assert_eq!(a.wide_sub(b), fits(wa - wb));
+                assert_eq!(a.wide_mul(b), fits(wa * wb));
+                let checked = a.checked_add(b);
+                assert!(checked.is_none() || checked == a.wide_add(b));
+                assert_eq!(a.checked_mul(b), a.wide_mul(b));
+            }
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!("3/4".parse(), Ok(Rational::new(3, 4)));
+        assert_eq!("-6/8".parse(), Ok(Rational::new(-3, 4)));
+        assert_eq!("6/-8".parse(), Ok(Rational::new(-3, 4)));
+        assert_eq!("42".parse(), Ok(Rational::from_integer(42)));
+        assert_eq!("3.125".parse(), Ok(Rational::new(25, 8)));
+        assert_eq!("-0.5".parse(), Ok(Rational::new(-1, 2)));
+        assert_eq!("+2.50".parse(), Ok(Rational::new(5, 2)));
+        assert_eq!("007.000".parse(), Ok(Rational::from_integer(7)));
+        for s in [
+            "1/0", "", "/", "1/", "a/b", ".5", "5.", "-.5", "1.-5", "1.2.3", "1/2/3",
+        ]
+        .iter()
+        {
+            assert_eq!(s.parse::<Rational<i64>>(), Err(ParseRationalError), "{}", s);
+        }
+        assert_eq!("-1/2".parse::<Rational<u32>>(), Err(ParseRationalError));
+        // 分母 10^20 は i64 に収まらない
+        assert_eq!(
+            "0.00000000000000000001".parse::<Rational<i64>>(),
+            Err(ParseRationalError)
+        );
+        assert_eq!(
+            "0.00000000000000000001".parse::<Rational<i128>>(),
+            Ok(Rational::new(1, 10_i128.pow(20)))
+        );
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(Rational::new(3, 4).to_string(), "3/4");
+        assert_eq!(Rational::new(6, -8).to_string(), "-3/4");
+        assert_eq!(Rational::new(10, 5).to_string(), "2");
+        assert_eq!(format!("{:?}", Rational::new(10, 5)), "(2 / 1)");
+        assert_eq!(format!("{:.3}", Rational::new(1, 3)), "0.333");
+        assert_eq!(format!("{:.3}", Rational::new(2, 3)), "0.667");
+        assert_eq!(format!("{:.0}", Rational::new(5, 2)), "3");
+        assert_eq!(format!("{:.0}", Rational::new(-5, 2)), "-3");
+        assert_eq!(Rational::new(-1, 3).to_decimal_string(2), "-0.33");
+        assert_eq!(
+            Rational::new(i64::MIN, 1).to_decimal_string(1),
+            "-9223372036854775808.0"
+        );
+        assert_eq!(
+            Rational::new(i64::MIN, 3).to_decimal_string(2),
+            "-3074457345618258602.67"
+        );
+        assert_eq!(Rational::new(-1, 1000).to_decimal_string(2), "0.00");
+        assert_eq!(Rational::new(9995, 1000).to_decimal_string(2), "10.00");
+        assert_eq!(Rational::new(-9995, 1000).to_decimal_string(2), "-10.00");
+        assert_eq!(Rational::new(1, 8).to_decimal_string(5), "0.12500");
+        assert_eq!(Rational::new(7, 1).to_decimal_string(1), "7.0");
+        assert_eq!(Rational::<u8>::new(255, 254).to_decimal_string(4), "1.0039");
+        // 10 * 余り が i64 に収まらない分母でも正確に丸める
+        let x = Rational::new(i64::MAX - 1, i64::MAX);
+        assert_eq!(x.to_decimal_string(18), "1.000000000000000000");
+        assert_eq!(x.to_decimal_string(19), "0.9999999999999999999");
+        for p in -50..50_i64 {
+            for q in 1..50 {
+                let x = Rational::new(p, q);
+                let s = x.to_decimal_string(4);
+                // 丸めた値との差は 0.00005 以下
+                let r: Rational<i64> = s.parse().unwrap();
+                assert!((r - x).abs() <= Rational::new(1, 20000));
+            }
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Rational::new(1, 4).to_f64(), 0.25);
+        assert_eq!(Rational::new(-7, 2).to_f64(), -3.5);
+        assert!((Rational::new(1, 3).to_f64() - 1.0 / 3.0).abs() < 1e-15);
+        assert_eq!(
+            Rational::new(i64::MIN, 1).to_f64(),
+            -9_223_372_036_854_775_808.0
+        );
+        assert_eq!(Rational::new(1, u128::MAX).to_f64(), 1.0 / u128::MAX as f64);
+        {
+            use crate::bigint::BigInt;
+            // 分子・分母とも f64 の範囲を超える
+            let p = Int::pow(BigInt::from(10_u32), 400);
+            let x = Rational::new(p.clone(), p.clone() * BigInt::from(3_u32) + BigInt::one());
+            assert!((x.to_f64() - 1.0 / 3.0).abs() < 1e-15);
+            let x = Rational::new(-p.clone(), BigInt::from(7_u32));
+            assert_eq!(x.to_f64(), f64::NEG_INFINITY);
+            let x = Rational::new(BigInt::from(7_u32), p.clone());
+            assert_eq!(x.to_f64(), 0.0);
+            let x = Rational::new(p + BigInt::one(), BigInt::from(7_u32));
+            let modulo = 1_000_000_007;
+            let expected = (ModInt::new(10, modulo).pow(400) + 1) * ModInt::new(7, modulo).inv();
+            assert_eq!(x.to_mint(modulo), expected);
+        }
+        assert_eq!(
+            Rational::new(i64::MIN, 1).to_mint(1_000_000_007),
+            ModInt::new(i64::MIN % 1_000_000_007, 1_000_000_007)
+        );
+        assert_eq!(Rational::new(-3_i8, 7).to_mint(11) * 7, 11 - 3);
+        let modulo = 998_244_353;
+        let x = Rational::new(-7, 3).to_mint(modulo);
+        assert_eq!(x * 3, modulo - 7);
+        assert_eq!(Rational::new(1, 2).to_mint(modulo), (modulo + 1) / 2);
+        let big = Rational::new(u128::MAX, 7);
+        assert_eq!(
+            big.to_mint(modulo) * 7,
+            ModInt::new((u128::MAX % modulo as u128) as i64, modulo)
+        );
+    }
+
+    #[test]
+    fn neg_abs_recip_pow() {
+        let x = Rational::new(-2, 3);
+        assert_eq!(-x, Rational::new(2, 3));
+        assert_eq!(x.abs(), Rational::new(2, 3));
+        assert_eq!(x.recip(), Rational::new(-3, 2));
+        assert_eq!(Rational::new(5, 1).recip(), Rational::new(1, 5));
+        assert_eq!(x.pow(3), Rational::new(-8, 27));
+        assert_eq!(x.pow(-2), Rational::new(9, 4));
+        assert_eq!(x.pow(0), 1);
+        assert_eq!(Rational::new(0, 1).pow(0), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn recip_of_zero() {
+        Rational::new(0, 5).recip();
+    }
+
+    #[test]
+    fn hash_sum_product() {
+        use std::collections::HashSet;
+        let set: HashSet<Rational<i64>> = vec![
+            Rational::new(1, 2),
+            Rational::new(2, 4),
+            Rational::new(-3, -6),
+            Rational::new(-1, 2),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 2);
+        let v: Vec<Rational<i64>> = (1..=4).map(|i| Rational::new(1, i)).collect();
+        assert_eq!(v.iter().sum::<Rational<i64>>(), Rational::new(25, 12));
+        assert_eq!(v.iter().product::<Rational<i64>>(), Rational::new(1, 24));
+        assert_eq!(v.into_iter().sum::<Rational<i64>>(), Rational::new(25, 12));
+        let empty: Vec<Rational<i64>> = vec![];
+        assert_eq!(empty.iter().product::<Rational<i64>>(), 1);
+    }
 }
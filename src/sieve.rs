@@ -0,0 +1,282 @@
+use crate::integer::Int;
+use cargo_snippet::snippet;
+
+/// 線形篩
+/// n 以下の各整数の最小素因数を O(n) で求めておき、素因数分解などに使う
+#[snippet("SIEVE")]
+#[derive(Clone, Debug)]
+pub struct Sieve {
+    /// spf[i]: i の最小素因数 (i < 2 のときは 0)
+    spf: Vec<usize>,
+    primes: Vec<usize>,
+}
+
+#[snippet("SIEVE")]
+impl Sieve {
+    /// Time complexity: O(n)
+    pub fn new(n: usize) -> Self {
+        let mut spf = vec![0; n + 1];
+        let mut primes = Vec::new();
+        for i in 2..=n {
+            if spf[i] == 0 {
+                spf[i] = i;
+                primes.push(i);
+            }
+            // i * p の最小素因数が p になるもの (p <= spf[i]) だけを書き込む
+            for &p in primes.iter() {
+                if p > spf[i] || i * p > n {
+                    break;
+                }
+                spf[i * p] = p;
+            }
+        }
+        Self { spf, primes }
+    }
+
+    /// 篩の上限 n
+    pub fn max(&self) -> usize {
+        self.spf.len() - 1
+    }
+
+    /// n 以下の素数 (昇順)
+    pub fn primes(&self) -> &[usize] {
+        &self.primes
+    }
+
+    pub fn is_prime(&self, x: usize) -> bool {
+        assert!(x <= self.max());
+        x >= 2 && self.spf[x] == x
+    }
+
+    /// `x` の最小素因数 (x < 2 のときは `None`)
+    pub fn smallest_prime_factor(&self, x: usize) -> Option<usize> {
+        assert!(x <= self.max());
+        if x < 2 {
+            None
+        } else {
+            Some(self.spf[x])
+        }
+    }
+
+    /// `x` を素因数分解し、(素因数, 指数) を素因数の昇順に返す
+    /// Time complexity: O(log x)
+    pub fn factorize(&self, x: usize) -> Vec<(usize, usize)> {
+        assert!(x <= self.max());
+        let mut ret: Vec<(usize, usize)> = Vec::new();
+        let mut x = x;
+        while x >= 2 {
+            let p = self.spf[x];
+            match ret.last_mut() {
+                Some((q, e)) if *q == p => *e += 1,
+                _ => ret.push((p, 1)),
+            }
+            x /= p;
+        }
+        ret
+    }
+
+    /// `x` の約数 (昇順)
+    /// Time complexity: O(d(x) log d(x))
+    pub fn divisors(&self, x: usize) -> Vec<usize> {
+        assert!(x >= 1);
+        let mut ret = vec![1];
+        for (p, e) in self.factorize(x) {
+            let len = ret.len();
+            let mut pk = 1;
+            for _ in 0..e {
+                pk *= p;
+                for i in 0..len {
+                    ret.push(ret[i] * pk);
+                }
+            }
+        }
+        ret.sort_unstable();
+        ret
+    }
+
+    /// オイラーの φ 関数の表 φ(0), φ(1), ..., φ(n) (φ(0) = 0 とする)
+    /// Time complexity: O(n)
+    pub fn euler_phi_table(&self) -> Vec<usize> {
+        let n = self.max();
+        let mut phi = vec![0; n + 1];
+        if n >= 1 {
+            phi[1] = 1;
+        }
+        for i in 2..=n {
+            let p = self.spf[i];
+            let j = i / p;
+            phi[i] = if self.spf[j] == p {
+                phi[j] * p
+            } else {
+                phi[j] * (p - 1)
+            };
+        }
+        phi
+    }
+
+    /// メビウス関数の表 μ(0), μ(1), ..., μ(n) (μ(0) = 0 とする)
+    /// Time complexity: O(n)
+    pub fn mobius_table(&self) -> Vec<i64> {
+        let n = self.max();
+        let mut mu = vec![0; n + 1];
+        if n >= 1 {
+            mu[1] = 1;
+        }
+        for i in 2..=n {
+            let p = self.spf[i];
+            let j = i / p;
+            mu[i] = if self.spf[j] == p { 0 } else { -mu[j] };
+        }
+        mu
+    }
+}
+
+/// 区間篩
+/// [l, r] の各整数が素数かどうかを返す (`ret[i]` が l + i に対応)
+/// Time complexity: O((r - l) log log r + sqrt(r))
+#[snippet("SEGMENTED_SIEVE", include = "SIEVE", include = "INT_TRAIT")]
+pub fn segmented_sieve(l: u64, r: u64) -> Vec<bool> {
+    if l > r {
+        return Vec::new();
+    }
+    let mut ret = vec![true; (r - l + 1) as usize];
+    for x in l..l.max(2).min(r + 1) {
+        ret[(x - l) as usize] = false;
+    }
+    let base = Sieve::new(r.sqrt_floor() as usize);
+    for &p in base.primes() {
+        let p = p as u64;
+        // p 自身は消さないよう p^2 から始める
+        let start = (p * p).max(l.div_ceil(p) * p);
+        for x in (start..=r).step_by(p as usize) {
+            ret[(x - l) as usize] = false;
+        }
+    }
+    ret
+}
+
+/// [l, r] に含まれる素数 (昇順)
+#[snippet("SEGMENTED_SIEVE")]
+pub fn primes_in_range(l: u64, r: u64) -> Vec<u64> {
+    segmented_sieve(l, r)
+        .into_iter()
+        .zip(l..)
+        .filter(|&(is_prime, _)| is_prime)
+        .map(|(_, x)| x)
+        .collect()
+}
+
+/// [l, r] の各整数を素因数分解し、(素因数, 指数) を素因数の昇順に返す (`ret[i]` が l + i に対応)
+/// sqrt(r) 以下の素数で割り切った残りが 1 でなければ、それは sqrt(r) より大きい素数
+/// Time complexity: O((r - l) log r + sqrt(r))
+#[snippet("SEGMENTED_SIEVE")]
+pub fn factorize_range(l: u64, r: u64) -> Vec<Vec<(u64, usize)>> {
+    if l > r {
+        return Vec::new();
+    }
+    let mut rest: Vec<u64> = (l..=r).collect();
+    let mut ret = vec![Vec::new(); rest.len()];
+    let base = Sieve::new(r.sqrt_floor() as usize);
+    for &p in base.primes() {
+        let p = p as u64;
+        let start = l.div_ceil(p).max(1) * p;
+        for x in (start..=r).step_by(p as usize) {
+            let i = (x - l) as usize;
+            let mut e = 0;
+            while rest[i].is_multiple_of(p) {
+                rest[i] /= p;
+                e += 1;
+            }
+            ret[i].push((p, e));
+        }
+    }
+    for (factors, &x) in ret.iter_mut().zip(rest.iter()) {
+        if x > 1 {
+            factors.push((x, 1));
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::integer::{divosors, factorize, gcd, lower_primes};
+
+    #[test]
+    fn test_sieve_primes() {
+        for n in 0..100 {
+            let sieve = Sieve::new(n);
+            assert_eq!(sieve.primes(), &lower_primes(n)[..]);
+        }
+        let sieve = Sieve::new(1_000_000);
+        assert_eq!(sieve.primes().len(), 78498);
+        assert!(sieve.is_prime(999_983));
+        assert!(!sieve.is_prime(1_000_000));
+        assert!(!sieve.is_prime(0));
+        assert!(!sieve.is_prime(1));
+        assert_eq!(sieve.smallest_prime_factor(1), None);
+        assert_eq!(sieve.smallest_prime_factor(999_999), Some(3));
+    }
+
+    #[test]
+    fn test_sieve_factorize() {
+        let sieve = Sieve::new(10000);
+        assert_eq!(sieve.factorize(1), vec![]);
+        assert_eq!(sieve.factorize(360), vec![(2, 3), (3, 2), (5, 1)]);
+        for x in 1..=10000 {
+            let mut expected: Vec<(usize, usize)> = factorize(x).into_iter().collect();
+            expected.sort_unstable();
+            assert_eq!(sieve.factorize(x), expected);
+            assert_eq!(sieve.divisors(x), divosors(x));
+        }
+    }
+
+    #[test]
+    fn test_sieve_tables() {
+        let n = 1000;
+        let sieve = Sieve::new(n);
+        let phi = sieve.euler_phi_table();
+        let mu = sieve.mobius_table();
+        assert_eq!(phi[0], 0);
+        assert_eq!(mu[0], 0);
+        for i in 1..=n {
+            assert_eq!(phi[i], (1..=i).filter(|&j| gcd(i, j) == 1).count());
+            let expected = sieve
+                .factorize(i)
+                .iter()
+                .fold(1, |acc, &(_, e)| if e >= 2 { 0 } else { -acc });
+            assert_eq!(mu[i], expected);
+        }
+        assert_eq!(&mu[1..11], &[1, -1, -1, 0, -1, 1, -1, 0, 0, 1]);
+    }
+
+    #[test]
+    fn test_segmented_sieve() {
+        let sieve = Sieve::new(2000);
+        for l in 0..50 {
+            for r in l..(l + 200) {
+                let expected: Vec<bool> = (l..=r).map(|x| sieve.is_prime(x as usize)).collect();
+                assert_eq!(segmented_sieve(l, r), expected);
+            }
+        }
+        assert_eq!(segmented_sieve(10, 9), vec![]);
+        // π(10^12 + 1000) - π(10^12) = 37
+        let l = 1_000_000_000_000_u64;
+        let primes = primes_in_range(l, l + 1000);
+        assert_eq!(primes.len(), 37);
+        assert!(primes.iter().all(|&p| crate::integer::is_prime(p)));
+    }
+
+    #[test]
+    fn test_factorize_range() {
+        let l = 999_999_999_000_u64;
+        let r = l + 300;
+        for (factors, x) in factorize_range(l, r).into_iter().zip(l..) {
+            let mut expected: Vec<(u64, usize)> = factorize(x).into_iter().collect();
+            expected.sort_unstable();
+            assert_eq!(factors, expected);
+        }
+        assert_eq!(factorize_range(0, 2), vec![vec![], vec![], vec![(2, 1)]]);
+    }
+}
@@ -0,0 +1,159 @@
+use cargo_snippet::snippet;
+
+/// u64 の XOR 基底 (GF(2)^64 の部分空間)
+/// 基底は常に簡約階段形に保つ
+/// (各基底の最上位ビットは互いに異なり、その位置のビットは他の基底では立っていない)
+#[snippet("XOR_BASIS")]
+#[derive(Clone, Debug)]
+pub struct XorBasis {
+    /// basis[b]: 最上位ビットが b の基底 (なければ 0)
+    basis: [u64; 64],
+    rank: usize,
+}
+
+#[snippet("XOR_BASIS")]
+impl Default for XorBasis {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[snippet("XOR_BASIS")]
+impl XorBasis {
+    pub fn new() -> Self {
+        Self {
+            basis: [0; 64],
+            rank: 0,
+        }
+    }
+
+    /// 基底の本数
+    pub fn rank(&self) -> usize {
+        self.rank
+    }
+
+    /// 昇順に並べた基底
+    pub fn basis(&self) -> Vec<u64> {
+        self.basis.iter().copied().filter(|&b| b != 0).collect()
+    }
+
+    fn reduce(&self, x: u64) -> u64 {
+        let mut x = x;
+        for bit in (0..64).rev() {
+            if x >> bit & 1 == 1 {
+                x ^= self.basis[bit];
+            }
+        }
+        x
+    }
+
+    /// `x` を追加する。既存の基底と一次独立だった (= 基底が増えた) なら `true`
+    /// Time complexity: O(64)
+    pub fn insert(&mut self, x: u64) -> bool {
+        let x = self.reduce(x);
+        if x == 0 {
+            return false;
+        }
+        let top = 63 - x.leading_zeros() as usize;
+        for b in self.basis.iter_mut() {
+            if *b >> top & 1 == 1 {
+                *b ^= x;
+            }
+        }
+        self.basis[top] = x;
+        self.rank += 1;
+        true
+    }
+
+    /// `x` が基底の張る空間に含まれるか
+    pub fn contains(&self, x: u64) -> bool {
+        self.reduce(x) == 0
+    }
+
+    /// 部分空間の要素の最大値
+    pub fn max_xor(&self) -> u64 {
+        self.max_xor_with(0)
+    }
+
+    /// 部分空間の要素 `y` について `x ^ y` の最大値
+    pub fn max_xor_with(&self, x: u64) -> u64 {
+        let mut x = x;
+        for bit in (0..64).rev() {
+            if x >> bit & 1 == 0 {
+                x ^= self.basis[bit];
+            }
+        }
+        x
+    }
+
+    /// 部分空間の 0 でない要素の最小値 (部分空間が {0} なら `None`)
+    pub fn min_xor(&self) -> Option<u64> {
+        self.basis.iter().copied().find(|&b| b != 0)
+    }
+
+    /// 部分空間の要素 `y` について `x ^ y` の最小値
+    pub fn min_xor_with(&self, x: u64) -> u64 {
+        self.reduce(x)
+    }
+
+    /// 部分空間の要素を昇順に並べたときの k 番目 (0-indexed, 0 番目は 0)
+    /// k が要素数 2^rank 以上なら `None`
+    pub fn kth_smallest(&self, k: u64) -> Option<u64> {
+        if self.rank < 64 && k >> self.rank != 0 {
+            return None;
+        }
+        Some(
+            self.basis()
+                .iter()
+                .enumerate()
+                .filter(|&(i, _)| k >> i & 1 == 1)
+                .fold(0, |acc, (_, &b)| acc ^ b),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xor_basis() {
+        let mut basis = XorBasis::new();
+        assert!(basis.insert(0b1100));
+        assert!(basis.insert(0b1010));
+        assert!(!basis.insert(0b0110));
+        assert!(basis.insert(0b0001));
+        assert!(!basis.insert(0));
+        assert_eq!(basis.rank(), 3);
+        assert!(basis.contains(0b0111));
+        assert!(!basis.contains(0b1000));
+        assert_eq!(basis.max_xor(), 0b1101);
+        assert_eq!(basis.max_xor_with(0b0010), 0b1111);
+        assert_eq!(basis.min_xor(), Some(0b0001));
+        assert_eq!(basis.min_xor_with(0b1000), 0b0010);
+        assert_eq!(XorBasis::new().min_xor(), None);
+    }
+
+    #[test]
+    fn test_kth_smallest() {
+        let values = [5_u64, 9, 12, 7, 5, 30];
+        let mut basis = XorBasis::new();
+        for &v in values.iter() {
+            basis.insert(v);
+        }
+        // enumerate the span naively
+        let mut span = vec![0_u64];
+        for &v in values.iter() {
+            let added: Vec<u64> = span.iter().map(|&s| s ^ v).collect();
+            span.extend(added);
+        }
+        span.sort_unstable();
+        span.dedup();
+        assert_eq!(span.len(), 1 << basis.rank());
+        for (k, &s) in span.iter().enumerate() {
+            assert_eq!(basis.kth_smallest(k as u64), Some(s));
+        }
+        assert_eq!(basis.kth_smallest(span.len() as u64), None);
+        assert_eq!(basis.max_xor(), *span.last().unwrap());
+    }
+}
@@ -1,3 +1,6 @@
+// get! の展開結果に含まれる `.trim().split_whitespace()` を許容する
+#![allow(clippy::trim_split_whitespace)]
+
 use libprocon::get;
 use std::io::{BufRead, BufReader};
 